@@ -0,0 +1,59 @@
+// cargo bench --bench throughput
+// For numbers on your own files, see the rolling-hash-bench binary instead
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use rolling_hash_rust::compare::compare_with_block_size;
+use rolling_hash_rust::windows::window_hashes;
+use rolling_hash_rust::RollingHash;
+
+const LEN: usize = 1 << 20;
+const WINDOW: usize = 64;
+
+fn random_bytes(len: usize, seed: u64) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    StdRng::seed_from_u64(seed).fill_bytes(&mut bytes);
+    bytes
+}
+
+fn throughput(c: &mut Criterion) {
+    let data = random_bytes(LEN, 42);
+    let mut group = c.benchmark_group("throughput");
+    group.throughput(Throughput::Bytes(LEN as u64));
+
+    group.bench_function("push_back", |b| {
+        b.iter(|| {
+            let mut rh = RollingHash::with_capacity(LEN);
+            for &byte in &data {
+                rh.push_back(byte);
+            }
+            rh.get_current_hash()
+        })
+    });
+    group.bench_function("roll", |b| {
+        b.iter(|| {
+            let mut rh = RollingHash::with_max_window(WINDOW);
+            for &byte in &data {
+                rh.push_back(byte);
+            }
+            rh.get_current_hash()
+        })
+    });
+    group.bench_function("bulk", |b| {
+        b.iter(|| RollingHash::from_initial_bytes(black_box(&data)).get_current_hash())
+    });
+    group.bench_function("window_hashes", |b| {
+        b.iter(|| window_hashes(black_box(&data), WINDOW).fold(0, |acc, hash| acc ^ hash))
+    });
+
+    // Half of the target is copied from the basis, half is new
+    let mut target = data.clone();
+    target[LEN / 2..].copy_from_slice(&random_bytes(LEN / 2, 7));
+    group.bench_function("compare", |b| {
+        b.iter(|| compare_with_block_size(black_box(&data), black_box(&target), 2048).len())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, throughput);
+criterion_main!(benches);
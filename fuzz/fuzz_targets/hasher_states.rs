@@ -0,0 +1,25 @@
+// cargo +nightly fuzz run hasher_states
+// Whatever state the Arbitrary impl produces (bounds, byte maps, reversed hash),
+// every edit must leave a hash that matches the bytes actually held
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rolling_hash_rust::operation::Operation;
+use rolling_hash_rust::params;
+use rolling_hash_rust::RollingHash;
+
+fuzz_target!(|input: (RollingHash, Vec<Operation>)| {
+    let (mut rh, operations) = input;
+    for operation in operations {
+        operation.apply(&mut rh);
+        let bytes = rh.get_current_bytes();
+        assert_eq!(rh.get_current_hash(), params::DEFAULT.hash(&bytes));
+        if let Some(max) = rh.max_window() {
+            assert!(bytes.len() <= max);
+        }
+        if let Some(reversed) = rh.get_reversed_hash() {
+            let reversed_bytes: Vec<u8> = bytes.iter().rev().copied().collect();
+            assert_eq!(reversed, params::DEFAULT.hash(&reversed_bytes));
+        }
+    }
+});
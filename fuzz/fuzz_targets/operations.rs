@@ -0,0 +1,54 @@
+// cargo +nightly fuzz run operations
+// Random edits to a RollingHash, checked after every step against a window of
+// plain bytes hashed from scratch, forwards and backwards
+#![no_main]
+
+use std::collections::VecDeque;
+
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+use rolling_hash_rust::operation::Operation;
+use rolling_hash_rust::RollingHash;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    max_window: Option<u8>,
+    initial: Vec<u8>,
+    operations: Vec<Operation>,
+}
+
+// Deliberately the most obvious implementation, sharing no code with the crate
+fn naive_hash<'a>(bytes: impl Iterator<Item = &'a u8>) -> u64 {
+    bytes.fold(0, |hash, &b| (hash * 257 + b as u64) % 1_000_000_007)
+}
+
+fuzz_target!(|input: Input| {
+    let max_window = input.max_window.map(|max| max as usize + 1);
+    let mut rh = match max_window {
+        Some(max) => RollingHash::with_max_window(max),
+        None => RollingHash::new(),
+    };
+    rh.track_reversed_hash();
+    let mut model = VecDeque::new();
+
+    let initial = input.initial.iter().copied().map(Operation::PushBack);
+    for operation in initial.chain(input.operations) {
+        operation.apply(&mut rh);
+        // A full window evicts from the opposite end before pushing
+        match (operation, max_window) {
+            (Operation::PushBack(_), Some(max)) if model.len() == max => {
+                model.pop_front();
+            }
+            (Operation::PushFront(_), Some(max)) if model.len() == max => {
+                model.pop_back();
+            }
+            _ => {}
+        }
+        operation.apply_to_bytes(&mut model);
+
+        assert_eq!(rh.len(), model.len());
+        assert_eq!(rh.get_current_hash(), naive_hash(model.iter()));
+        assert_eq!(rh.get_reversed_hash(), Some(naive_hash(model.iter().rev())));
+    }
+    assert_eq!(rh.get_current_bytes(), Vec::from(model));
+});
@@ -0,0 +1,52 @@
+/* C interface to rolling_hash_rust, available with the `ffi` feature.
+ *
+ * Build a static or dynamic library with:
+ *   cargo rustc --release --features ffi --crate-type staticlib
+ *   cargo rustc --release --features ffi --crate-type cdylib
+ *
+ * Kept in the layout cbindgen produces, so it can be regenerated with:
+ *   cbindgen --config cbindgen.toml --output include/rolling_hash.h
+ */
+
+#ifndef ROLLING_HASH_H
+#define ROLLING_HASH_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef struct RollingHash RollingHash;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+RollingHash *rolling_hash_new(void);
+
+RollingHash *rolling_hash_with_max_window(size_t max_window);
+
+void rolling_hash_free(RollingHash *rh);
+
+void rolling_hash_push_back(RollingHash *rh, uint8_t b);
+
+void rolling_hash_push_front(RollingHash *rh, uint8_t b);
+
+void rolling_hash_pop_front(RollingHash *rh);
+
+void rolling_hash_pop_back(RollingHash *rh);
+
+void rolling_hash_roll(RollingHash *rh, uint8_t b);
+
+void rolling_hash_extend(RollingHash *rh, const uint8_t *data, size_t len);
+
+uint64_t rolling_hash_get_hash(const RollingHash *rh);
+
+size_t rolling_hash_len(const RollingHash *rh);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ROLLING_HASH_H */
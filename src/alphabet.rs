@@ -0,0 +1,71 @@
+// Ready-made byte maps for RollingHash::set_byte_map()
+
+// 'A'..='Z' and 'a'..='z' become the same 26 symbols
+pub fn fold_case(b: u8) -> u8 {
+    b.to_ascii_lowercase()
+}
+
+// Every digit becomes '0', so numbers of the same length look alike
+pub fn collapse_digits(b: u8) -> u8 {
+    if b.is_ascii_digit() {
+        b'0'
+    } else {
+        b
+    }
+}
+
+pub fn fold_case_and_collapse_digits(b: u8) -> u8 {
+    collapse_digits(fold_case(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::{collapse_digits, fold_case, fold_case_and_collapse_digits};
+    use crate::RollingHash;
+
+    fn mapped_hash(map: fn(u8) -> u8, bytes: &[u8]) -> u64 {
+        let mut rh = RollingHash::new();
+        rh.set_byte_map(map);
+        rh.extend_from_slice(bytes);
+        rh.get_current_hash()
+    }
+
+    #[test]
+    fn case_is_ignored() {
+        assert_eq!(
+            mapped_hash(fold_case, b"EIGER"),
+            mapped_hash(fold_case, b"eiger")
+        );
+        assert_eq!(
+            mapped_hash(fold_case, b"Eiger"),
+            RollingHash::from_initial_bytes(b"eiger").get_current_hash()
+        );
+    }
+
+    #[test]
+    fn digits_are_collapsed() {
+        assert_eq!(
+            mapped_hash(collapse_digits, b"Eiger 3970m"),
+            mapped_hash(collapse_digits, b"Eiger 1234m")
+        );
+        assert_ne!(
+            mapped_hash(collapse_digits, b"Eiger 3970m"),
+            mapped_hash(collapse_digits, b"Eiger 397m")
+        );
+        assert_eq!(
+            mapped_hash(fold_case_and_collapse_digits, b"EIGER 3970M"),
+            mapped_hash(fold_case_and_collapse_digits, b"eiger 1234m")
+        );
+    }
+
+    #[test]
+    fn every_entry_point_is_mapped() {
+        let mut rh = RollingHash::with_max_window(5);
+        rh.set_byte_map(fold_case);
+        rh.push_back(b'I');
+        rh.push_front(b'E');
+        rh.extend_from_slice(b"GER");
+        rh.set_byte(0, b'T');
+        assert_eq!(rh.get_current_bytes(), b"tiger");
+    }
+}
@@ -0,0 +1,52 @@
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::{alphabet, RollingHash};
+
+// A hasher in any configuration the public API can produce: bounded or not,
+// with or without the reversed hash and a byte map, holding arbitrary bytes.
+// Windows stay small, so fuzzers reach eviction quickly
+impl<'a> Arbitrary<'a> for RollingHash {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut rh = match u.arbitrary::<Option<u8>>()? {
+            Some(max_window) => RollingHash::with_max_window(max_window as usize + 1),
+            None => RollingHash::new(),
+        };
+        if u.arbitrary()? {
+            rh.track_reversed_hash();
+        }
+        if u.arbitrary()? {
+            let maps: [fn(u8) -> u8; 3] = [
+                alphabet::fold_case,
+                alphabet::collapse_digits,
+                alphabet::fold_case_and_collapse_digits,
+            ];
+            rh.set_byte_map(*u.choose(&maps)?);
+        }
+        rh.extend_from_slice(u.arbitrary()?);
+        Ok(rh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use crate::operation::Operation;
+    use crate::params;
+    use crate::RollingHash;
+
+    #[test]
+    fn generated_hashers_are_consistent() {
+        for seed in 0..100u8 {
+            let data: Vec<u8> = (0..64u8).map(|i| i.wrapping_mul(seed) ^ seed).collect();
+            let mut u = Unstructured::new(&data);
+            let mut rh = RollingHash::arbitrary(&mut u).unwrap();
+            for operation in Vec::<Operation>::arbitrary(&mut u).unwrap() {
+                operation.apply(&mut rh);
+            }
+            let bytes = rh.get_current_bytes();
+            assert_eq!(rh.get_current_hash(), params::DEFAULT.hash(&bytes));
+            assert!(rh.max_window().is_none_or(|max| bytes.len() <= max));
+        }
+    }
+}
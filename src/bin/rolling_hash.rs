@@ -0,0 +1,149 @@
+// Command line access to the library, e.g.
+//   rolling-hash hash notes.txt
+//   cat notes.txt | rolling-hash hash --window 16
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use rolling_hash_rust::params;
+use rolling_hash_rust::windows::RollingHashIteratorExt;
+use rolling_hash_rust::{Error, RollingHash};
+
+#[derive(Parser)]
+#[command(
+    name = "rolling-hash",
+    about = "Polynomial rolling hashes from the command line"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    // clap turns doc comments into help text, hence the `///`
+    /// Print the hash of a file (or stdin), or of every window in it
+    Hash {
+        /// Reads stdin when omitted or "-"
+        file: Option<PathBuf>,
+        /// Print "offset hash" for every window of N bytes instead
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+        window: Option<u64>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Hash { file, window } => open_input(file)
+            .and_then(|input| hash(input, window.map(|w| w as usize), io::stdout().lock())),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // Output piped into `head` is not worth an error message
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("rolling-hash: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn open_input(file: Option<PathBuf>) -> io::Result<Box<dyn Read>> {
+    match file {
+        Some(path) if path.as_os_str() != "-" => match File::open(&path) {
+            Ok(file) => Ok(Box::new(file)),
+            Err(error) => Err(io::Error::new(
+                error.kind(),
+                format!("{}: {error}", path.display()),
+            )),
+        },
+        _ => Ok(Box::new(io::stdin().lock())),
+    }
+}
+
+// Streams the input, so windows are printed while a pipeline is still writing
+// and memory does not grow with the input
+fn hash(input: impl Read, window: Option<usize>, out: impl Write) -> io::Result<()> {
+    let input = BufReader::new(input);
+    let mut out = BufWriter::new(out);
+    match window {
+        Some(window) => {
+            let mut read_error = None;
+            let bytes = input
+                .bytes()
+                .map_while(|byte| byte.map_err(|error| read_error = Some(error)).ok());
+            for (offset, hash) in bytes.rolling_hashes(window).enumerate() {
+                writeln!(out, "{offset} {hash}")?;
+            }
+            if let Some(error) = read_error {
+                return Err(error);
+            }
+        }
+        None => {
+            let (hash, len) = RollingHash::hash_reader(input).map_err(|error| match error {
+                Error::Io(error) => error,
+                error => io::Error::other(error),
+            })?;
+            writeln!(
+                out,
+                "hash={hash} len={len} profile={}",
+                params::DEFAULT.name
+            )?;
+        }
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read};
+
+    use rolling_hash_rust::RollingHash;
+
+    use crate::hash;
+
+    #[test]
+    fn whole_input() {
+        let mut out = Vec::new();
+        hash(&b"Eiger"[..], None, &mut out).unwrap();
+        let expected = RollingHash::from_initial_bytes(b"Eiger").get_current_hash();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("hash={expected} len=5 profile=1e9+7/257\n")
+        );
+    }
+
+    #[test]
+    fn one_line_per_window() {
+        let mut out = Vec::new();
+        hash(&b"Eiger"[..], Some(4), &mut out).unwrap();
+        let eige = RollingHash::from_initial_bytes(b"Eige").get_current_hash();
+        let iger = RollingHash::from_initial_bytes(b"iger").get_current_hash();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("0 {eige}\n1 {iger}\n")
+        );
+    }
+
+    #[test]
+    fn read_errors_stop_the_windows() {
+        let failing = io::Cursor::new(b"Eiger").chain(FailingReader);
+        let mut out = Vec::new();
+        let error = hash(failing, Some(4), &mut out).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        let mut out = Vec::new();
+        assert!(hash(FailingReader, None, &mut out).is_err());
+    }
+
+    struct FailingReader;
+
+    impl Read for FailingReader {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::InvalidData.into())
+        }
+    }
+}
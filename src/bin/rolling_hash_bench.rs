@@ -0,0 +1,138 @@
+// Throughput of the main operations on your own files, e.g.
+//   rolling-hash-bench --window 48 disk.img notes.txt
+// Each operation runs over the whole file a few times and the best run counts.
+// `cargo bench` covers the same operations on synthetic data
+
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use rolling_hash_rust::windows::window_hashes;
+use rolling_hash_rust::RollingHash;
+
+#[derive(Parser)]
+#[command(
+    name = "rolling-hash-bench",
+    about = "Measure rolling hash throughput on your own files"
+)]
+struct Cli {
+    // clap turns doc comments into help text, hence the `///`
+    /// Files to read; each is loaded into memory before timing starts
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+    /// Window size for the rolling operations
+    #[arg(long, default_value_t = 64, value_parser = clap::value_parser!(u64).range(1..))]
+    window: u64,
+    /// How many times to run each operation
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u64).range(1..))]
+    rounds: u64,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut out = BufWriter::new(io::stdout().lock());
+    for path in &cli.files {
+        let result = fs::read(path)
+            .map_err(|error| io::Error::new(error.kind(), format!("{}: {error}", path.display())))
+            .and_then(|data| {
+                let report = bench(&data, cli.window as usize, cli.rounds as usize);
+                print_report(&path.display().to_string(), &report, &mut out)
+            });
+        match result {
+            Ok(()) => {}
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => return ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("rolling-hash-bench: {error}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+// (operation, bytes processed, best time)
+type Report = Vec<(&'static str, usize, Duration)>;
+
+fn bench(data: &[u8], window: usize, rounds: usize) -> Report {
+    let operations: [(&'static str, &dyn Fn() -> u64); 4] = [
+        ("push_back", &|| {
+            let mut rh = RollingHash::with_capacity(data.len());
+            for &byte in data {
+                rh.push_back(byte);
+            }
+            rh.get_current_hash()
+        }),
+        ("roll", &|| {
+            let mut rh = RollingHash::with_max_window(window);
+            for &byte in data {
+                rh.push_back(byte);
+            }
+            rh.get_current_hash()
+        }),
+        ("bulk", &|| {
+            RollingHash::from_initial_bytes(data).get_current_hash()
+        }),
+        ("window_hashes", &|| {
+            window_hashes(data, window).fold(0, |acc, hash| acc ^ hash)
+        }),
+    ];
+    operations
+        .into_iter()
+        .map(|(name, operation)| {
+            let best = (0..rounds)
+                .map(|_| {
+                    let start = Instant::now();
+                    std::hint::black_box(operation());
+                    start.elapsed()
+                })
+                .min()
+                .unwrap_or_default();
+            (name, data.len(), best)
+        })
+        .collect()
+}
+
+fn print_report(file: &str, report: &Report, mut out: impl Write) -> io::Result<()> {
+    for &(name, bytes, elapsed) in report {
+        let seconds = elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            let mb_per_second = bytes as f64 / seconds / 1e6;
+            writeln!(out, "{file}\t{name}\t{mb_per_second:.1} MB/s")?;
+        } else {
+            writeln!(out, "{file}\t{name}\t- MB/s")?;
+        }
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{bench, print_report};
+
+    #[test]
+    fn every_operation_is_reported() {
+        let report = bench(b"Eiger, Moench and Jungfrau", 4, 2);
+        let names: Vec<_> = report.iter().map(|&(name, ..)| name).collect();
+        assert_eq!(names, ["push_back", "roll", "bulk", "window_hashes"]);
+        assert!(report.iter().all(|&(_, bytes, _)| bytes == 26));
+    }
+
+    #[test]
+    fn megabytes_per_second() {
+        let report = vec![
+            ("bulk", 3_000_000, Duration::from_millis(500)),
+            ("roll", 10, Duration::ZERO),
+        ];
+        let mut out = Vec::new();
+        print_report("disk.img", &report, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "disk.img\tbulk\t6.0 MB/s\ndisk.img\troll\t- MB/s\n"
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::hash::{BuildHasher, Hasher};
+
+use crate::modular::Modular;
+use crate::{bulk, Numeric, RollingHash, BIG_PRIME};
+
+// Hashing for HashMap/HashSet with the crate's polynomial hash, e.g.
+//   HashMap::with_hasher(PolynomialBuildHasher::random())
+// The seed picks the base. With the default seed of 0 that is RollingHash's
+// base, and writing bytes gives the same polynomial hash as
+// RollingHash::from_initial_bytes() on them. Colliding keys are easy to craft
+// for a known base, so maps fed untrusted keys should use random()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PolynomialBuildHasher {
+    base: u64,
+}
+
+impl Default for PolynomialBuildHasher {
+    fn default() -> Self {
+        Self::with_seed(0)
+    }
+}
+
+impl PolynomialBuildHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Every seed gives a base in RollingHash::BASE..BIG_PRIME
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            base: RollingHash::BASE + seed % (BIG_PRIME - RollingHash::BASE),
+        }
+    }
+
+    // A secret base from the OS's random source, drawn anew on every call
+    pub fn random() -> Self {
+        use rand::Rng;
+        Self::with_seed(rand::rngs::OsRng.gen())
+    }
+
+    // E.g. for RollingHash::set_base(), to get the same hashes from a RollingHash
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+}
+
+impl BuildHasher for PolynomialBuildHasher {
+    type Hasher = PolynomialHasher;
+
+    fn build_hasher(&self) -> PolynomialHasher {
+        PolynomialHasher {
+            hash: Modular::from_u64(0),
+            base: self.base,
+        }
+    }
+}
+
+// Note that Hash for slices, Vec and str writes the length before the bytes,
+// so only write() itself lines up with the rolling hashes
+#[derive(Debug, Clone)]
+pub struct PolynomialHasher {
+    hash: Numeric,
+    base: u64,
+}
+
+// Polynomial hashes are below 2^30, but HashMap takes its control bits from the
+// top of the 64-bit value. Multiplying by an odd constant is a bijection, so keys
+// still collide in the map exactly when their polynomial hashes do
+const SPREAD: u64 = 0x9e37_79b9_7f4a_7c15;
+
+impl PolynomialHasher {
+    // The hash of everything written so far, as RollingHash would report it
+    pub fn polynomial_hash(&self) -> u64 {
+        self.hash.value()
+    }
+}
+
+impl Hasher for PolynomialHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        if self.base == RollingHash::BASE {
+            self.hash = bulk::extend(self.hash, bytes);
+            return;
+        }
+        self.hash = bytes
+            .iter()
+            .fold(self.hash, |hash, &b| hash * self.base + b as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.polynomial_hash().wrapping_mul(SPREAD)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::hash::{BuildHasher, Hasher};
+
+    use crate::build_hasher::PolynomialBuildHasher;
+    use crate::RollingHash;
+
+    #[test]
+    fn matches_rolling_hash() {
+        let mut hasher = PolynomialBuildHasher::new().build_hasher();
+        hasher.write(b"Eig");
+        hasher.write(b"er");
+        assert_eq!(
+            hasher.polynomial_hash(),
+            RollingHash::from_initial_bytes(b"Eiger").get_current_hash()
+        );
+    }
+
+    #[test]
+    fn seeds_change_the_hash() {
+        let hash = |build: PolynomialBuildHasher| build.hash_one(b"Eiger");
+        assert_eq!(
+            hash(PolynomialBuildHasher::new()),
+            hash(PolynomialBuildHasher::with_seed(0))
+        );
+        assert_ne!(
+            hash(PolynomialBuildHasher::new()),
+            hash(PolynomialBuildHasher::with_seed(42))
+        );
+    }
+
+    #[test]
+    fn seeds_pick_the_base() {
+        let build = PolynomialBuildHasher::with_seed(42);
+        let mut hasher = build.build_hasher();
+        hasher.write(b"Eiger");
+        let mut rh = RollingHash::from_initial_bytes(b"Eiger");
+        rh.set_base(build.base());
+        assert_eq!(hasher.polynomial_hash(), rh.get_current_hash());
+    }
+
+    #[test]
+    fn collisions_do_not_survive_another_seed() {
+        // Same hash with the default base (see hash_collision_example in lib.rs)
+        let s1 = b"ryIqVm6i3M25uvTttp2Qo8mlkWmKap5PkuWHtS3AZZkRBWCAE9jGCWpkgYHaQobJDJrhdwdoNRGjqQmaTAi5ZGo6hbslnzIL2HaP";
+        let s2 = b"eVCblKi7jexBFHudJsTfj8ibzxgXGlol8EthCd8OBniEXI6tVR9LFkNzPtNeqR3EIVERZwtG1uxFimT3cPQAHwTTiuRnj6gHh406";
+        let hash = |build: PolynomialBuildHasher, bytes: &[u8]| {
+            let mut hasher = build.build_hasher();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+        let build = PolynomialBuildHasher::new();
+        assert_eq!(hash(build, s1), hash(build, s2));
+        let build = PolynomialBuildHasher::random();
+        assert_ne!(hash(build, s1), hash(build, s2));
+    }
+
+    #[test]
+    fn works_as_a_map_hasher() {
+        let mut heights = HashMap::with_hasher(PolynomialBuildHasher::with_seed(7));
+        heights.insert("Eiger", 3967);
+        heights.insert("Jungfrau", 4158);
+        assert_eq!(heights.get("Eiger"), Some(&3967));
+        assert_eq!(heights.get("Moench"), None);
+    }
+}
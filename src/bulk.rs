@@ -0,0 +1,193 @@
+use crate::{Numeric, RollingHash, BIG_PRIME};
+
+// Bulk hashing works on blocks: appending a block of BLOCK bytes is
+//   hash * BASE^BLOCK + sum(b_i * BASE^(BLOCK-1-i))
+// The sum is a dot product of the bytes with fixed powers, which needs no modular
+// reduction until the end (each term is below 2^38), so it vectorizes nicely
+const BLOCK: usize = 16;
+
+const fn block_powers() -> [u64; BLOCK] {
+    let mut powers = [0; BLOCK];
+    let mut power = 1;
+    let mut i = BLOCK;
+    while i > 0 {
+        i -= 1;
+        powers[i] = power;
+        power = power * RollingHash::BASE % BIG_PRIME;
+    }
+    powers
+}
+
+const fn base_to_block() -> u64 {
+    let mut power = 1;
+    let mut i = 0;
+    while i < BLOCK {
+        power = power * RollingHash::BASE % BIG_PRIME;
+        i += 1;
+    }
+    power
+}
+
+// BLOCK_POWERS[i] = BASE^(BLOCK-1-i)
+const BLOCK_POWERS: [u64; BLOCK] = block_powers();
+const BASE_TO_BLOCK: u64 = base_to_block();
+
+// Appends `bytes` to a hash, as if they were pushed back one by one
+pub(crate) fn extend(hash: Numeric, bytes: &[u8]) -> Numeric {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if is_x86_feature_detected!("avx2") {
+        // Safety: we just checked that the CPU supports AVX2
+        return unsafe { x86::extend_avx2(hash, bytes) };
+    }
+
+    // NEON is part of the aarch64 baseline, so there is nothing to detect
+    #[cfg(all(feature = "simd", target_arch = "aarch64"))]
+    let dot = aarch64::dot_neon;
+    #[cfg(not(all(feature = "simd", target_arch = "aarch64")))]
+    let dot = dot_scalar;
+
+    extend_with(hash, bytes, dot)
+}
+
+#[inline(always)]
+fn extend_with(mut hash: Numeric, bytes: &[u8], dot: impl Fn(&[u8; BLOCK]) -> u64) -> Numeric {
+    let mut blocks = bytes.chunks_exact(BLOCK);
+    for block in &mut blocks {
+        hash = hash * BASE_TO_BLOCK + dot(block.try_into().unwrap());
+    }
+    for &b in blocks.remainder() {
+        hash = hash * RollingHash::BASE + b as u64;
+    }
+    hash
+}
+
+// Only used by tests when the NEON path is compiled in
+#[cfg_attr(all(feature = "simd", target_arch = "aarch64"), allow(dead_code))]
+fn dot_scalar(block: &[u8; BLOCK]) -> u64 {
+    block
+        .iter()
+        .zip(BLOCK_POWERS)
+        .map(|(&b, power)| b as u64 * power)
+        .sum()
+}
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    use super::{extend_with, BLOCK, BLOCK_POWERS};
+    use crate::Numeric;
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn extend_avx2(hash: Numeric, bytes: &[u8]) -> Numeric {
+        extend_with(hash, bytes, |block| unsafe { dot_avx2(block) })
+    }
+
+    #[target_feature(enable = "avx2")]
+    unsafe fn dot_avx2(block: &[u8; BLOCK]) -> u64 {
+        let mut sums = _mm256_setzero_si256();
+        for i in 0..BLOCK / 4 {
+            let four_bytes = i32::from_le_bytes(block[4 * i..4 * i + 4].try_into().unwrap());
+            // Widen 4 bytes into 4 u64 lanes
+            let bytes = _mm256_cvtepu8_epi64(_mm_cvtsi32_si128(four_bytes));
+            let powers = _mm256_loadu_si256(BLOCK_POWERS.as_ptr().add(4 * i) as *const __m256i);
+            // Powers are below BIG_PRIME, so they fit in the 32 bits this multiplies
+            sums = _mm256_add_epi64(sums, _mm256_mul_epu32(bytes, powers));
+        }
+
+        let mut lanes = [0u64; 4];
+        _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, sums);
+        lanes.iter().sum()
+    }
+}
+
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+mod aarch64 {
+    use std::arch::aarch64::*;
+
+    use super::{BLOCK, BLOCK_POWERS};
+
+    // Powers are below BIG_PRIME, so they fit in 32 bits
+    const BLOCK_POWERS_U32: [u32; BLOCK] = {
+        let mut powers = [0; BLOCK];
+        let mut i = 0;
+        while i < BLOCK {
+            powers[i] = BLOCK_POWERS[i] as u32;
+            i += 1;
+        }
+        powers
+    };
+
+    pub(super) fn dot_neon(block: &[u8; BLOCK]) -> u64 {
+        // Safety: NEON is part of the aarch64 baseline, and all loads are in bounds
+        unsafe {
+            let bytes = vld1q_u8(block.as_ptr());
+            let low = vmovl_u8(vget_low_u8(bytes));
+            let high = vmovl_high_u8(bytes);
+            let widened = [
+                vmovl_u16(vget_low_u16(low)),
+                vmovl_high_u16(low),
+                vmovl_u16(vget_low_u16(high)),
+                vmovl_high_u16(high),
+            ];
+
+            let mut sums = vdupq_n_u64(0);
+            for (i, bytes) in widened.into_iter().enumerate() {
+                let powers = vld1q_u32(BLOCK_POWERS_U32.as_ptr().add(4 * i));
+                sums = vaddq_u64(sums, vmull_u32(vget_low_u32(bytes), vget_low_u32(powers)));
+                sums = vaddq_u64(sums, vmull_high_u32(bytes, powers));
+            }
+            vaddvq_u64(sums)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+
+    use crate::bulk::{dot_scalar, extend, extend_with, BLOCK, BLOCK_POWERS};
+    use crate::modular::Modular;
+    use crate::{Numeric, RollingHash};
+
+    fn hash_byte_by_byte(bytes: &[u8]) -> u64 {
+        let mut rh = RollingHash::new();
+        bytes.iter().for_each(|&b| rh.push_back(b));
+        rh.get_current_hash()
+    }
+
+    #[test]
+    fn block_powers_are_descending_powers_of_base() {
+        let mut expected: Numeric = Modular::from_u64(1);
+        for i in (0..BLOCK).rev() {
+            assert_eq!(BLOCK_POWERS[i], expected.value);
+            expected = expected * RollingHash::BASE;
+        }
+    }
+
+    #[test]
+    fn scalar_matches_byte_by_byte() {
+        let bytes: Vec<u8> = (0..100u8).map(|i| i.wrapping_mul(37)).collect();
+        for len in 0..bytes.len() {
+            let hash = extend_with(Modular::from_u64(0), &bytes[..len], dot_scalar);
+            assert_eq!(hash.value, hash_byte_by_byte(&bytes[..len]));
+        }
+    }
+
+    #[test]
+    fn worst_case_block_does_not_overflow() {
+        let hash = extend(Modular::from_u64(1_000_000_006), &[255; 4 * BLOCK]);
+        let mut rh = RollingHash::new();
+        rh.current_hash = Modular::from_u64(1_000_000_006);
+        [255; 4 * BLOCK].iter().for_each(|&b| rh.push_back(b));
+        assert_eq!(hash.value, rh.get_current_hash());
+    }
+
+    proptest! {
+        #[test]
+        fn extend_matches_byte_by_byte(bytes in proptest::collection::vec(0..=255u8, 0..200)) {
+            let hash = extend(Modular::from_u64(0), &bytes);
+            assert_eq!(hash.value, hash_byte_by_byte(&bytes));
+        }
+    }
+}
@@ -0,0 +1,579 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::progress::{Progress, ProgressSink};
+use crate::{Error, RollingHash};
+
+// Which parts of `target` can be copied from `basis`, in the style of rsync: the
+// basis is cut into fixed-size blocks, and a window rolls over the target looking
+// for them. Only the structure is reported, nothing is serialized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    // target[target_offset..][..len] == bases[basis][basis_offset..][..len], where
+    // `basis` is always 0 when comparing against a single basis
+    Match {
+        basis: usize,
+        basis_offset: usize,
+        target_offset: usize,
+        len: usize,
+    },
+    // target[target_offset..][..len] has no counterpart in the basis
+    Literal {
+        target_offset: usize,
+        len: usize,
+    },
+}
+
+// What a comparison found and how much work it took, e.g. to pick a block size
+// from real data
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CompareStats {
+    pub bytes_matched: usize,
+    pub literal_bytes: usize,
+    // Match regions, after merging adjacent blocks
+    pub copy_ops: usize,
+    // Windows whose hash was found among the basis blocks, whether or not the
+    // bytes agreed
+    pub weak_hits: usize,
+    // Weak hits where no basis block had the same bytes
+    pub false_positives: usize,
+    pub elapsed: Duration,
+}
+
+impl CompareStats {
+    // Target bytes per second, None when the comparison took no measurable time
+    pub fn throughput(&self) -> Option<f64> {
+        let seconds = self.elapsed.as_secs_f64();
+        (seconds > 0.0).then(|| (self.bytes_matched + self.literal_bytes) as f64 / seconds)
+    }
+}
+
+pub const DEFAULT_BLOCK_SIZE: usize = 2048;
+
+const PROGRESS_INTERVAL: usize = 1 << 16;
+
+pub fn compare(basis: &[u8], target: &[u8]) -> Vec<Region> {
+    compare_with_block_size(basis, target, DEFAULT_BLOCK_SIZE)
+}
+
+// The regions cover the whole target, in order. Adjacent matches that are also
+// adjacent in the basis are merged into one
+pub fn compare_with_block_size(basis: &[u8], target: &[u8], block_size: usize) -> Vec<Region> {
+    compare_with_progress(basis, target, block_size, &mut ())
+}
+
+// Reports to `progress` after every PROGRESS_INTERVAL bytes of the target, and
+// once more at the end
+pub fn compare_with_progress(
+    basis: &[u8],
+    target: &[u8],
+    block_size: usize,
+    progress: &mut impl ProgressSink,
+) -> Vec<Region> {
+    let never_cancelled = AtomicBool::new(false);
+    compare_cancellable(basis, target, block_size, progress, &never_cancelled)
+        .expect("nothing can set the flag")
+}
+
+// Gives up with Error::Cancelled soon after `cancel` is set, e.g. from another
+// thread. The flag is checked every PROGRESS_INTERVAL bytes of basis and target
+pub fn compare_cancellable(
+    basis: &[u8],
+    target: &[u8],
+    block_size: usize,
+    progress: &mut impl ProgressSink,
+    cancel: &AtomicBool,
+) -> crate::Result<Vec<Region>> {
+    compare_bases(
+        &[basis],
+        target,
+        block_size,
+        progress,
+        cancel,
+        &mut CompareStats::default(),
+    )
+}
+
+// Like compare_with_block_size, along with statistics about the comparison
+pub fn compare_with_stats(
+    basis: &[u8],
+    target: &[u8],
+    block_size: usize,
+) -> (Vec<Region>, CompareStats) {
+    let never_cancelled = AtomicBool::new(false);
+    let mut stats = CompareStats::default();
+    let start = Instant::now();
+    let regions = compare_bases(
+        &[basis],
+        target,
+        block_size,
+        &mut (),
+        &never_cancelled,
+        &mut stats,
+    )
+    .expect("nothing can set the flag");
+    stats.elapsed = start.elapsed();
+    (regions, stats)
+}
+
+// Like compare_with_block_size, but blocks may come from any of `bases`, e.g.
+// several older versions of a file. When a block occurs in more than one basis,
+// the first one wins, unless another continues the previous match
+pub fn compare_many(bases: &[&[u8]], target: &[u8], block_size: usize) -> Vec<Region> {
+    let never_cancelled = AtomicBool::new(false);
+    compare_bases(
+        bases,
+        target,
+        block_size,
+        &mut (),
+        &never_cancelled,
+        &mut CompareStats::default(),
+    )
+    .expect("nothing can set the flag")
+}
+
+// Where a basis block starts
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Block {
+    basis: usize,
+    offset: usize,
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "debug",
+        skip_all,
+        fields(bases = bases.len(), target_len = target.len(), block_size)
+    )
+)]
+fn compare_bases(
+    bases: &[&[u8]],
+    target: &[u8],
+    block_size: usize,
+    progress: &mut impl ProgressSink,
+    cancel: &AtomicBool,
+    stats: &mut CompareStats,
+) -> crate::Result<Vec<Region>> {
+    assert!(block_size > 0, "block_size must be positive");
+    let check_cancelled = || {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(Error::Cancelled);
+        }
+        Ok(())
+    };
+
+    let mut blocks: HashMap<u64, Vec<Block>> = HashMap::new();
+    let blocks_per_check = PROGRESS_INTERVAL.div_ceil(block_size);
+    for (basis, bytes) in bases.iter().enumerate() {
+        for (index, block) in bytes.chunks_exact(block_size).enumerate() {
+            if index % blocks_per_check == 0 {
+                check_cancelled()?;
+            }
+            let hash = RollingHash::from_initial_bytes(block).get_current_hash();
+            let offset = index * block_size;
+            blocks
+                .entry(hash)
+                .or_default()
+                .push(Block { basis, offset });
+        }
+    }
+
+    let mut regions = Vec::new();
+    let mut literal_start = 0;
+    let mut start = 0;
+    let mut window = RollingHash::with_max_window(block_size);
+    window.extend_from_slice(&target[..block_size.min(target.len())]);
+    let mut bytes_matched = 0;
+    let mut next_report = PROGRESS_INTERVAL;
+    let report = |bytes_read: usize, bytes_matched: usize| Progress {
+        bytes_read: bytes_read as u64,
+        bytes_matched: bytes_matched as u64,
+        total_bytes: Some(target.len() as u64),
+    };
+
+    while start + block_size <= target.len() {
+        if start >= next_report {
+            check_cancelled()?;
+            progress.report(report(start, bytes_matched));
+            next_report = start + PROGRESS_INTERVAL;
+        }
+        let candidate = &target[start..start + block_size];
+        let mut found = None;
+        if let Some(blocks) = blocks.get(&window.get_current_hash()) {
+            stats.weak_hits += 1;
+            found = find_block(bases, blocks, candidate, regions.last());
+            if found.is_none() {
+                stats.false_positives += 1;
+            }
+        }
+
+        if let Some(block) = found {
+            push_literal(&mut regions, literal_start, start);
+            push_match(&mut regions, block, start, block_size);
+            bytes_matched += block_size;
+            start += block_size;
+            literal_start = start;
+            window.reset();
+            window.extend_from_slice(&target[start..(start + block_size).min(target.len())]);
+        } else if start + block_size < target.len() {
+            window.push_back(target[start + block_size]);
+            start += 1;
+        } else {
+            break;
+        }
+    }
+    push_literal(&mut regions, literal_start, target.len());
+    progress.report(report(target.len(), bytes_matched));
+    stats.bytes_matched = bytes_matched;
+    stats.literal_bytes = target.len() - bytes_matched;
+    stats.copy_ops = regions
+        .iter()
+        .filter(|region| matches!(region, Region::Match { .. }))
+        .count();
+    #[cfg(feature = "tracing")]
+    trace_summary(stats);
+    Ok(regions)
+}
+
+#[cfg(feature = "tracing")]
+fn trace_summary(stats: &CompareStats) {
+    let target_len = stats.bytes_matched + stats.literal_bytes;
+    let hit_rate = if target_len == 0 {
+        1.0
+    } else {
+        stats.bytes_matched as f64 / target_len as f64
+    };
+    tracing::debug!(
+        matches = stats.copy_ops,
+        matched_bytes = stats.bytes_matched,
+        literal_bytes = stats.literal_bytes,
+        weak_hits = stats.weak_hits,
+        false_positives = stats.false_positives,
+        hit_rate,
+        "compared"
+    );
+}
+
+// Verifying a candidate costs a full block comparison, so on repetitive data
+// (where every block has the same hash) we give up after this many
+const MAX_VERIFIED: usize = 16;
+
+// Among the basis blocks with the right hash, one with the same bytes,
+// preferring the block right after the previous match. `blocks` is in basis
+// and offset order, so the continuation is found without touching any bytes
+fn find_block(
+    bases: &[&[u8]],
+    blocks: &[Block],
+    candidate: &[u8],
+    previous: Option<&Region>,
+) -> Option<Block> {
+    let same_bytes = |block: &&Block| {
+        &bases[block.basis][block.offset..block.offset + candidate.len()] == candidate
+    };
+    if let Some(&Region::Match {
+        basis,
+        basis_offset,
+        len,
+        ..
+    }) = previous
+    {
+        let continuation = (basis, basis_offset + len);
+        if let Ok(index) =
+            blocks.binary_search_by_key(&continuation, |block| (block.basis, block.offset))
+        {
+            if same_bytes(&&blocks[index]) {
+                return Some(blocks[index]);
+            }
+        }
+    }
+    blocks.iter().take(MAX_VERIFIED).find(same_bytes).copied()
+}
+
+fn push_literal(regions: &mut Vec<Region>, start: usize, end: usize) {
+    if start < end {
+        regions.push(Region::Literal {
+            target_offset: start,
+            len: end - start,
+        });
+    }
+}
+
+fn push_match(regions: &mut Vec<Region>, block: Block, target_offset: usize, len: usize) {
+    if let Some(Region::Match {
+        basis: previous_basis,
+        basis_offset: previous_offset,
+        target_offset: previous_target,
+        len: previous_len,
+    }) = regions.last_mut()
+    {
+        if *previous_basis == block.basis
+            && *previous_offset + *previous_len == block.offset
+            && *previous_target + *previous_len == target_offset
+        {
+            *previous_len += len;
+            return;
+        }
+    }
+    regions.push(Region::Match {
+        basis: block.basis,
+        basis_offset: block.offset,
+        target_offset,
+        len,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+
+    use proptest::prelude::*;
+
+    use crate::compare::{
+        compare, compare_cancellable, compare_many, compare_with_block_size, compare_with_progress,
+        compare_with_stats, Region,
+    };
+    use crate::progress::Progress;
+
+    #[test]
+    fn identical_files_are_one_match() {
+        let data = b"Eiger, Moench and Jungfrau";
+        assert_eq!(
+            compare_with_block_size(data, data, 4),
+            vec![
+                Region::Match {
+                    basis: 0,
+                    basis_offset: 0,
+                    target_offset: 0,
+                    len: 24
+                },
+                Region::Literal {
+                    target_offset: 24,
+                    len: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn insertion_in_the_middle() {
+        let basis = b"EigerMonchJungfrau!!";
+        let target = b"EigerMonch and the Jungfrau!!";
+        assert_eq!(
+            compare_with_block_size(basis, target, 5),
+            vec![
+                Region::Match {
+                    basis: 0,
+                    basis_offset: 0,
+                    target_offset: 0,
+                    len: 10
+                },
+                Region::Literal {
+                    target_offset: 10,
+                    len: 9
+                },
+                Region::Match {
+                    basis: 0,
+                    basis_offset: 10,
+                    target_offset: 19,
+                    len: 10
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_in_common() {
+        assert_eq!(
+            compare(b"Eiger", b"Tiger"),
+            vec![Region::Literal {
+                target_offset: 0,
+                len: 5
+            }]
+        );
+        assert_eq!(compare(b"Eiger", b""), vec![]);
+    }
+
+    #[test]
+    fn progress_is_reported() {
+        let basis = vec![1u8; 200_000];
+        let mut target = basis.clone();
+        target[100_000] = 2;
+        let mut reports = Vec::new();
+        let regions =
+            compare_with_progress(&basis, &target, 1000, &mut |p: Progress| reports.push(p));
+
+        assert_eq!(regions, compare_with_block_size(&basis, &target, 1000));
+        assert!(reports.len() > 2);
+        assert!(reports
+            .windows(2)
+            .all(|w| w[0].bytes_read < w[1].bytes_read));
+        let last = reports.last().unwrap();
+        assert_eq!(last.percent_complete(), Some(100.0));
+        assert_eq!(last.bytes_matched, 199_000);
+    }
+
+    #[test]
+    fn stats_count_matches_and_literals() {
+        let basis = b"EigerMonchJungfrau!!";
+        let target = b"EigerMonch and the Jungfrau!!";
+        let (regions, stats) = compare_with_stats(basis, target, 5);
+        assert_eq!(regions, compare_with_block_size(basis, target, 5));
+        assert_eq!(stats.bytes_matched, 20);
+        assert_eq!(stats.literal_bytes, 9);
+        assert_eq!(stats.copy_ops, 2);
+        assert_eq!(stats.weak_hits, 4);
+        assert_eq!(stats.false_positives, 0);
+    }
+
+    #[test]
+    fn cancellation_stops_the_comparison() {
+        let data = vec![1u8; 200_000];
+        let cancel = AtomicBool::new(true);
+        let result = compare_cancellable(&data, &data, 1000, &mut (), &cancel);
+        assert!(matches!(result, Err(crate::Error::Cancelled)));
+
+        let cancel = AtomicBool::new(false);
+        let regions = compare_cancellable(&data, &data, 1000, &mut (), &cancel).unwrap();
+        assert_eq!(regions, compare_with_block_size(&data, &data, 1000));
+    }
+
+    #[test]
+    fn repetitive_input_is_not_quadratic() {
+        // Every block has the same hash, so each match used to byte-compare
+        // all 16384 basis blocks
+        let zeros = vec![0; 1 << 20];
+        assert_eq!(
+            compare_with_block_size(&zeros, &zeros, 64),
+            vec![Region::Match {
+                basis: 0,
+                basis_offset: 0,
+                target_offset: 0,
+                len: 1 << 20
+            }]
+        );
+    }
+
+    #[test]
+    fn blocks_from_several_bases() {
+        let old: &[u8] = b"EigerMonch";
+        let sibling: &[u8] = b"JungfrauXX";
+        let target = b"EigerJungfrauMonch";
+        assert_eq!(
+            compare_many(&[old, sibling], target, 5),
+            vec![
+                Region::Match {
+                    basis: 0,
+                    basis_offset: 0,
+                    target_offset: 0,
+                    len: 5
+                },
+                Region::Match {
+                    basis: 1,
+                    basis_offset: 0,
+                    target_offset: 5,
+                    len: 5
+                },
+                Region::Literal {
+                    target_offset: 10,
+                    len: 3
+                },
+                Region::Match {
+                    basis: 0,
+                    basis_offset: 5,
+                    target_offset: 13,
+                    len: 5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn a_match_continues_in_the_same_basis() {
+        let first: &[u8] = b"aaaabbbb";
+        let second: &[u8] = b"ccccaaaabbbb";
+        let regions = compare_many(&[first, second], b"ccccaaaabbbb", 4);
+        assert_eq!(
+            regions,
+            vec![Region::Match {
+                basis: 1,
+                basis_offset: 0,
+                target_offset: 0,
+                len: 12
+            }]
+        );
+    }
+
+    #[test]
+    fn repetitive_bases_are_not_quadratic() {
+        let zeros = vec![0; 1 << 19];
+        let target = vec![0; 1 << 20];
+        let regions = compare_many(&[&zeros, &zeros], &target, 64);
+        // Past the end of the first basis, the search starts over from its start
+        assert_eq!(
+            regions,
+            vec![
+                Region::Match {
+                    basis: 0,
+                    basis_offset: 0,
+                    target_offset: 0,
+                    len: 1 << 19
+                },
+                Region::Match {
+                    basis: 0,
+                    basis_offset: 0,
+                    target_offset: 1 << 19,
+                    len: 1 << 19
+                },
+            ]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn regions_reconstruct_the_target(
+            basis in prop::collection::vec(0..4u8, 0..200),
+            target in prop::collection::vec(0..4u8, 0..200),
+            block_size in 1..8usize,
+        ) {
+            let mut rebuilt = Vec::new();
+            for region in compare_with_block_size(&basis, &target, block_size) {
+                match region {
+                    Region::Match { basis: index, basis_offset, target_offset, len } => {
+                        prop_assert_eq!(index, 0);
+                        prop_assert_eq!(target_offset, rebuilt.len());
+                        rebuilt.extend_from_slice(&basis[basis_offset..basis_offset + len]);
+                    }
+                    Region::Literal { target_offset, len } => {
+                        prop_assert_eq!(target_offset, rebuilt.len());
+                        rebuilt.extend_from_slice(&target[target_offset..target_offset + len]);
+                    }
+                }
+            }
+            prop_assert_eq!(rebuilt, target);
+        }
+
+        #[test]
+        fn regions_from_several_bases_reconstruct_the_target(
+            first in prop::collection::vec(0..4u8, 0..100),
+            second in prop::collection::vec(0..4u8, 0..100),
+            target in prop::collection::vec(0..4u8, 0..200),
+            block_size in 1..8usize,
+        ) {
+            let bases = [&first[..], &second[..]];
+            let mut rebuilt = Vec::new();
+            for region in compare_many(&bases, &target, block_size) {
+                match region {
+                    Region::Match { basis, basis_offset, len, .. } => {
+                        rebuilt.extend_from_slice(&bases[basis][basis_offset..basis_offset + len]);
+                    }
+                    Region::Literal { target_offset, len } => {
+                        rebuilt.extend_from_slice(&target[target_offset..target_offset + len]);
+                    }
+                }
+            }
+            prop_assert_eq!(rebuilt, target);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChunkLocation {
+    pub file_id: u64,
+    pub offset: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkEntry {
+    pub length: u64,
+    pub first_seen: ChunkLocation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Insertion {
+    // First time we see this content
+    New,
+    // Same content was already indexed here
+    Duplicate(ChunkEntry),
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct DedupIndex {
+    // Several entries under the same hash are hash collisions that
+    // were told apart by a verifier
+    entries: HashMap<u64, Vec<ChunkEntry>>,
+    unique_bytes: u64,
+    unique_chunks: usize,
+}
+
+impl DedupIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.unique_chunks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unique_chunks == 0
+    }
+
+    pub fn unique_bytes(&self) -> u64 {
+        self.unique_bytes
+    }
+
+    // Trusts the hash: an entry with the same hash and length is a duplicate
+    pub fn insert(&mut self, hash: u64, length: u64, location: ChunkLocation) -> Insertion {
+        self.insert_verified(hash, length, location, |_| true)
+    }
+
+    // `verify` is called for every indexed chunk with the same hash and length, and
+    // should compare the actual bytes (e.g. by reading them back from `first_seen`).
+    // If it rejects all of them, the chunk is a hash collision and gets its own entry
+    pub fn insert_verified(
+        &mut self,
+        hash: u64,
+        length: u64,
+        location: ChunkLocation,
+        verify: impl FnMut(&ChunkEntry) -> bool,
+    ) -> Insertion {
+        if let Some(&existing) = self.lookup_verified(hash, length, verify) {
+            return Insertion::Duplicate(existing);
+        }
+
+        self.entries.entry(hash).or_default().push(ChunkEntry {
+            length,
+            first_seen: location,
+        });
+        self.unique_bytes += length;
+        self.unique_chunks += 1;
+        Insertion::New
+    }
+
+    pub fn lookup(&self, hash: u64, length: u64) -> Option<&ChunkEntry> {
+        self.lookup_verified(hash, length, |_| true)
+    }
+
+    pub fn lookup_verified(
+        &self,
+        hash: u64,
+        length: u64,
+        mut verify: impl FnMut(&ChunkEntry) -> bool,
+    ) -> Option<&ChunkEntry> {
+        self.entries
+            .get(&hash)?
+            .iter()
+            .filter(|entry| entry.length == length)
+            .find(|entry| verify(entry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dedup::{ChunkEntry, ChunkLocation, DedupIndex, Insertion};
+    use crate::RollingHash;
+
+    fn location(file_id: u64, offset: u64) -> ChunkLocation {
+        ChunkLocation { file_id, offset }
+    }
+
+    fn hash(bytes: &[u8]) -> u64 {
+        RollingHash::from_initial_bytes(bytes).get_current_hash()
+    }
+
+    #[test]
+    fn first_insert_is_new() {
+        let mut index = DedupIndex::new();
+        assert_eq!(
+            index.insert(hash(b"Eiger"), 5, location(0, 0)),
+            Insertion::New
+        );
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.unique_bytes(), 5);
+    }
+
+    #[test]
+    fn duplicate_reports_first_seen_location() {
+        let mut index = DedupIndex::new();
+        index.insert(hash(b"Eiger"), 5, location(0, 10));
+        let insertion = index.insert(hash(b"Eiger"), 5, location(3, 99));
+        match insertion {
+            Insertion::Duplicate(entry) => assert_eq!(entry.first_seen, location(0, 10)),
+            Insertion::New => panic!("expected a duplicate"),
+        }
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.unique_bytes(), 5);
+    }
+
+    #[test]
+    fn same_hash_different_length_is_not_a_duplicate() {
+        let mut index = DedupIndex::new();
+        index.insert(1234, 5, location(0, 0));
+        assert_eq!(index.insert(1234, 6, location(0, 5)), Insertion::New);
+        assert!(index.lookup(1234, 5).is_some());
+        assert!(index.lookup(1234, 6).is_some());
+        assert!(index.lookup(1234, 7).is_none());
+    }
+
+    #[test]
+    fn verifier_separates_collisions() {
+        // Known collision, see `hash_collision_example` in lib.rs
+        let s1 = b"ryIqVm6i3M25uvTttp2Qo8mlkWmKap5PkuWHtS3AZZkRBWCAE9jGCWpkgYHaQobJDJrhdwdoNRGjqQmaTAi5ZGo6hbslnzIL2HaP";
+        let s2 = b"eVCblKi7jexBFHudJsTfj8ibzxgXGlol8EthCd8OBniEXI6tVR9LFkNzPtNeqR3EIVERZwtG1uxFimT3cPQAHwTTiuRnj6gHh406";
+        assert_eq!(hash(s1), hash(s2));
+
+        let files: [&[u8]; 2] = [s1, s2];
+        let verify_against = |bytes: &'static [u8]| {
+            move |entry: &ChunkEntry| files[entry.first_seen.file_id as usize] == bytes
+        };
+
+        let mut index = DedupIndex::new();
+        let insertion = index.insert_verified(hash(s1), 100, location(0, 0), verify_against(s1));
+        assert_eq!(insertion, Insertion::New);
+        let insertion = index.insert_verified(hash(s2), 100, location(1, 0), verify_against(s2));
+        assert_eq!(insertion, Insertion::New);
+        assert_eq!(index.len(), 2);
+
+        let found = index
+            .lookup_verified(hash(s2), 100, verify_against(s2))
+            .unwrap();
+        assert_eq!(found.first_seen, location(1, 0));
+    }
+}
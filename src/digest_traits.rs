@@ -0,0 +1,67 @@
+use digest::consts::U8;
+use digest::{FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update};
+
+use crate::RollingHash;
+
+// With these, RollingHash also gets the blanket `digest::Digest` implementation.
+// The output is the current hash as 8 big-endian bytes
+
+impl Update for RollingHash {
+    fn update(&mut self, data: &[u8]) {
+        self.extend_from_slice(data);
+    }
+}
+
+impl OutputSizeUser for RollingHash {
+    type OutputSize = U8;
+}
+
+impl FixedOutput for RollingHash {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.get_current_hash().to_be_bytes());
+    }
+}
+
+impl FixedOutputReset for RollingHash {
+    fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.get_current_hash().to_be_bytes());
+        RollingHash::reset(self);
+    }
+}
+
+impl Reset for RollingHash {
+    fn reset(&mut self) {
+        RollingHash::reset(self);
+    }
+}
+
+impl HashMarker for RollingHash {}
+
+#[cfg(test)]
+mod tests {
+    use digest::Digest;
+
+    use crate::RollingHash;
+
+    #[test]
+    fn digest_matches_the_rolling_hash() {
+        let output = <RollingHash as Digest>::digest(b"Eiger");
+        let expected = RollingHash::from_initial_bytes(b"Eiger").get_current_hash();
+        assert_eq!(output.as_slice(), expected.to_be_bytes());
+    }
+
+    #[test]
+    fn incremental_updates_and_reset() {
+        let mut hasher = <RollingHash as Digest>::new();
+        Digest::update(&mut hasher, b"Ei");
+        Digest::update(&mut hasher, b"ger");
+        let output = hasher.finalize_reset();
+        let expected = RollingHash::from_initial_bytes(b"Eiger").get_current_hash();
+        assert_eq!(output.as_slice(), expected.to_be_bytes());
+
+        Digest::update(&mut hasher, b"Tiger");
+        let output = hasher.finalize();
+        let expected = RollingHash::from_initial_bytes(b"Tiger").get_current_hash();
+        assert_eq!(output.as_slice(), expected.to_be_bytes());
+    }
+}
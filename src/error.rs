@@ -0,0 +1,54 @@
+use std::io;
+
+// Everything that can go wrong in the crate's fallible APIs, so callers only
+// need to match on one type. New failure kinds may be added
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    // Input that does not follow the expected layout
+    #[error("invalid format: {0}")]
+    Format(String),
+    // Data produced with different parameters than the ones in use,
+    // e.g. another hash profile
+    #[error("parameter mismatch: expected {expected}, found {found}")]
+    ParameterMismatch { expected: String, found: String },
+    // A hash matched, but the bytes behind it did not
+    #[error("verification failed: {0}")]
+    Verification(String),
+    // The caller asked a long-running operation to stop
+    #[error("operation cancelled")]
+    Cancelled,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crate::error::Error;
+
+    #[test]
+    fn io_errors_convert() {
+        fn read() -> crate::Result<()> {
+            Err(io::Error::new(io::ErrorKind::NotFound, "no Eiger here"))?
+        }
+        let error = read().unwrap_err();
+        assert!(matches!(error, Error::Io(_)));
+        assert_eq!(error.to_string(), "I/O error: no Eiger here");
+    }
+
+    #[test]
+    fn messages() {
+        let error = Error::ParameterMismatch {
+            expected: "1e9+7/257".to_string(),
+            found: "998244353/131".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "parameter mismatch: expected 1e9+7/257, found 998244353/131"
+        );
+    }
+}
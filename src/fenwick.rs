@@ -0,0 +1,218 @@
+use std::ops::Range;
+
+use crate::mismatch;
+use crate::modular::Modular;
+use crate::powers::PowerTable;
+use crate::{Numeric, RollingHash};
+
+// A lighter alternative to DynamicHashedString: a Fenwick tree over the byte
+// contributions b_i * BASE^(n-1-i), i.e. weighted as in the hash of the whole
+// string. A prefix sum is then the hash of that prefix scaled by BASE^(n-r),
+// which we divide out. Only n + 1 values are stored, and updates are cheaper
+pub struct FenwickHashedString {
+    bytes: Vec<u8>,
+    // 1-indexed, tree[i] covers the (i & -i) contributions ending at i - 1
+    tree: Vec<Numeric>,
+    base_powers: PowerTable,
+    // inverse_powers[k] = BASE^-k for k in 0..=n, to unscale prefix sums. The
+    // length never changes, so these are computed once
+    inverse_powers: Vec<Numeric>,
+}
+
+impl FenwickHashedString {
+    pub fn new(bytes: &[u8]) -> Self {
+        let n = bytes.len();
+        let mut base_powers = PowerTable::new();
+        base_powers.ensure(n);
+
+        // Linear-time construction: each node pushes its total to its parent
+        let mut tree = vec![Numeric::from_u64(0); n + 1];
+        for (i, &byte) in bytes.iter().enumerate() {
+            tree[i + 1] = tree[i + 1] + base_powers.get(n - 1 - i) * byte as u64;
+            let parent = (i + 1) + lowest_bit(i + 1);
+            if parent <= n {
+                tree[parent] = tree[parent] + tree[i + 1];
+            }
+        }
+
+        let base_inverse: Numeric = Modular::from_u64(RollingHash::BASE)
+            .try_inverse()
+            .expect("BASE must be coprime with BIG_PRIME");
+        let inverse_powers = std::iter::successors(Some(Numeric::from_u64(1)), |&power| {
+            Some(power * base_inverse)
+        })
+        .take(n + 1)
+        .collect();
+
+        Self {
+            bytes: bytes.to_vec(),
+            tree,
+            base_powers,
+            inverse_powers,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    // Panics if `index` is out of bounds
+    pub fn set(&mut self, index: usize, byte: u8) {
+        assert!(index < self.len(), "index out of bounds");
+        let old = self.bytes[index];
+        self.bytes[index] = byte;
+
+        let weight = self.base_powers.get(self.len() - 1 - index);
+        let delta = weight * byte as u64 - weight * old as u64;
+        let mut i = index + 1;
+        while i <= self.len() {
+            self.tree[i] = self.tree[i] + delta;
+            i += lowest_bit(i);
+        }
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.prefix_hash(self.len())
+    }
+
+    // The hash of bytes[..end]. Panics if `end` is out of bounds
+    pub fn prefix_hash(&self, end: usize) -> u64 {
+        self.range_hash(0..end)
+    }
+
+    // Panics if the range is out of bounds
+    pub fn range_hash(&self, range: Range<usize>) -> u64 {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range out of bounds"
+        );
+        let scaled = self.prefix_sum(range.end) - self.prefix_sum(range.start);
+        (scaled * self.inverse_powers[self.len() - range.end]).value()
+    }
+
+    // The first index where the two strings differ, or the length of the shorter
+    // one if it is a prefix of the other; None if they are equal. Probabilistic,
+    // see mismatch::first_mismatch()
+    pub fn first_mismatch(&self, other: &Self) -> Option<usize> {
+        mismatch::first_mismatch(
+            self.len(),
+            other.len(),
+            |range| self.range_hash(range),
+            |range| other.range_hash(range),
+        )
+    }
+
+    fn prefix_sum(&self, end: usize) -> Numeric {
+        let mut sum = Numeric::from_u64(0);
+        let mut i = end;
+        while i > 0 {
+            sum = sum + self.tree[i];
+            i -= lowest_bit(i);
+        }
+        sum
+    }
+}
+
+fn lowest_bit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+impl From<&[u8]> for FenwickHashedString {
+    fn from(bytes: &[u8]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::fenwick::FenwickHashedString;
+    use crate::RollingHash;
+
+    fn hash_from_bytes(bytes: &[u8]) -> u64 {
+        RollingHash::from_initial_bytes(bytes).get_current_hash()
+    }
+
+    #[test]
+    fn prefix_hashes_match_rolling_hash() {
+        let string = FenwickHashedString::new(b"Eiger");
+        assert_eq!(string.hash(), hash_from_bytes(b"Eiger"));
+        assert_eq!(string.prefix_hash(3), hash_from_bytes(b"Eig"));
+        assert_eq!(string.prefix_hash(0), 0);
+        assert_eq!(string.range_hash(1..4), hash_from_bytes(b"ige"));
+    }
+
+    #[test]
+    fn set_updates_the_hashes() {
+        let mut string = FenwickHashedString::new(b"Eiger");
+        string.set(0, b'T');
+        string.set(4, b'r');
+        assert_eq!(string.bytes(), b"Tiger");
+        assert_eq!(string.hash(), hash_from_bytes(b"Tiger"));
+        assert_eq!(string.prefix_hash(2), hash_from_bytes(b"Ti"));
+    }
+
+    #[test]
+    fn first_mismatch() {
+        let string = FenwickHashedString::new(b"Jungfrau");
+        assert_eq!(
+            string.first_mismatch(&FenwickHashedString::new(b"Jungfrau")),
+            None
+        );
+        assert_eq!(
+            string.first_mismatch(&FenwickHashedString::new(b"Jungle")),
+            Some(4)
+        );
+        assert_eq!(
+            string.first_mismatch(&FenwickHashedString::new(b"Jung")),
+            Some(4)
+        );
+        assert_eq!(
+            string.first_mismatch(&FenwickHashedString::new(b"")),
+            Some(0)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn every_prefix_after_updates(
+            bytes in prop::collection::vec(any::<u8>(), 1..40),
+            updates in prop::collection::vec((any::<usize>(), any::<u8>()), 0..10),
+        ) {
+            let mut expected = bytes.clone();
+            let mut string = FenwickHashedString::new(&bytes);
+            for (index, byte) in updates {
+                let index = index % expected.len();
+                expected[index] = byte;
+                string.set(index, byte);
+            }
+            for end in 0..=expected.len() {
+                prop_assert_eq!(string.prefix_hash(end), hash_from_bytes(&expected[..end]));
+            }
+        }
+
+        #[test]
+        fn first_mismatch_matches_a_byte_scan(
+            a in prop::collection::vec(0..3u8, 0..60),
+            b in prop::collection::vec(0..3u8, 0..60),
+        ) {
+            let scan = a.iter().zip(&b).position(|(x, y)| x != y);
+            let expected = match scan {
+                Some(index) => Some(index),
+                None if a.len() == b.len() => None,
+                None => Some(a.len().min(b.len())),
+            };
+            let (a, b) = (FenwickHashedString::new(&a), FenwickHashedString::new(&b));
+            prop_assert_eq!(a.first_mismatch(&b), expected);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// C interface, see include/rolling_hash.h. Build the shared library with
+//   cargo rustc --lib --release --features ffi --crate-type cdylib
+// Hashers are handed out as opaque pointers: create them with rolling_hash_new()
+// and release them with rolling_hash_free(). Passing NULL to any other function
+// is undefined behavior
+
+use crate::RollingHash;
+
+#[no_mangle]
+pub extern "C" fn rolling_hash_new() -> *mut RollingHash {
+    Box::into_raw(Box::new(RollingHash::new()))
+}
+
+// Returns NULL if `max_window` is 0
+#[no_mangle]
+pub extern "C" fn rolling_hash_with_max_window(max_window: usize) -> *mut RollingHash {
+    if max_window == 0 {
+        return std::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(RollingHash::with_max_window(max_window)))
+}
+
+/// # Safety
+/// `rh` must come from rolling_hash_new() or rolling_hash_with_max_window(),
+/// and must not be used afterwards. NULL is ignored
+#[no_mangle]
+pub unsafe extern "C" fn rolling_hash_free(rh: *mut RollingHash) {
+    if !rh.is_null() {
+        drop(Box::from_raw(rh));
+    }
+}
+
+/// # Safety
+/// `rh` must be a live hasher
+#[no_mangle]
+pub unsafe extern "C" fn rolling_hash_push_back(rh: *mut RollingHash, b: u8) {
+    (*rh).push_back(b);
+}
+
+/// # Safety
+/// `rh` must be a live hasher
+#[no_mangle]
+pub unsafe extern "C" fn rolling_hash_push_front(rh: *mut RollingHash, b: u8) {
+    (*rh).push_front(b);
+}
+
+/// # Safety
+/// `rh` must be a live hasher
+#[no_mangle]
+pub unsafe extern "C" fn rolling_hash_pop_front(rh: *mut RollingHash) {
+    (*rh).pop_front();
+}
+
+/// # Safety
+/// `rh` must be a live hasher
+#[no_mangle]
+pub unsafe extern "C" fn rolling_hash_pop_back(rh: *mut RollingHash) {
+    (*rh).pop_back();
+}
+
+/// # Safety
+/// `rh` must be a live hasher
+#[no_mangle]
+pub unsafe extern "C" fn rolling_hash_roll(rh: *mut RollingHash, b: u8) {
+    (*rh).pop_front();
+    (*rh).push_back(b);
+}
+
+/// # Safety
+/// `rh` must be a live hasher, and `data` must point to `len` readable bytes
+/// (it may be NULL when `len` is 0)
+#[no_mangle]
+pub unsafe extern "C" fn rolling_hash_extend(rh: *mut RollingHash, data: *const u8, len: usize) {
+    if len > 0 {
+        (*rh).extend_from_slice(std::slice::from_raw_parts(data, len));
+    }
+}
+
+/// # Safety
+/// `rh` must be a live hasher
+#[no_mangle]
+pub unsafe extern "C" fn rolling_hash_get_hash(rh: *const RollingHash) -> u64 {
+    (*rh).get_current_hash()
+}
+
+/// # Safety
+/// `rh` must be a live hasher
+#[no_mangle]
+pub unsafe extern "C" fn rolling_hash_len(rh: *const RollingHash) -> usize {
+    (*rh).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ffi::*;
+    use crate::RollingHash;
+
+    fn hash_from_string(string: &str) -> u64 {
+        RollingHash::from_initial_bytes(string.as_bytes()).get_current_hash()
+    }
+
+    #[test]
+    fn lifecycle_through_the_c_interface() {
+        unsafe {
+            let rh = rolling_hash_new();
+            let data = b"Eige";
+            rolling_hash_extend(rh, data.as_ptr(), data.len());
+            rolling_hash_push_back(rh, b'r');
+            assert_eq!(rolling_hash_get_hash(rh), hash_from_string("Eiger"));
+
+            rolling_hash_roll(rh, b'!');
+            assert_eq!(rolling_hash_get_hash(rh), hash_from_string("iger!"));
+            rolling_hash_pop_back(rh);
+            rolling_hash_push_front(rh, b'T');
+            rolling_hash_pop_front(rh);
+            assert_eq!(rolling_hash_get_hash(rh), hash_from_string("iger"));
+            assert_eq!(rolling_hash_len(rh), 4);
+
+            rolling_hash_extend(rh, std::ptr::null(), 0);
+            assert_eq!(rolling_hash_len(rh), 4);
+            rolling_hash_free(rh);
+        }
+    }
+
+    #[test]
+    fn bounded_window_through_the_c_interface() {
+        assert!(rolling_hash_with_max_window(0).is_null());
+        unsafe {
+            let rh = rolling_hash_with_max_window(3);
+            let data = b"Eiger";
+            rolling_hash_extend(rh, data.as_ptr(), data.len());
+            assert_eq!(rolling_hash_get_hash(rh), hash_from_string("ger"));
+            rolling_hash_free(rh);
+            rolling_hash_free(std::ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::modular::Modular;
+use crate::{bulk, RollingHash};
+
+const DEFAULT_BUFFER_SIZE: usize = 64 * 1024;
+
+impl RollingHash {
+    // (hash, length) of a whole file, read through a plain buffer. Unlike
+    // from_initial_bytes(), the contents are never held in memory all at once
+    pub fn hash_file(path: impl AsRef<Path>) -> crate::Result<(u64, u64)> {
+        Self::hash_file_with_buffer_size(path, DEFAULT_BUFFER_SIZE)
+    }
+
+    pub fn hash_file_with_buffer_size(
+        path: impl AsRef<Path>,
+        buffer_size: usize,
+    ) -> crate::Result<(u64, u64)> {
+        Self::hash_reader_with_buffer_size(File::open(path)?, buffer_size)
+    }
+
+    pub fn hash_reader(reader: impl Read) -> crate::Result<(u64, u64)> {
+        Self::hash_reader_with_buffer_size(reader, DEFAULT_BUFFER_SIZE)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(reader))
+    )]
+    pub fn hash_reader_with_buffer_size(
+        mut reader: impl Read,
+        buffer_size: usize,
+    ) -> crate::Result<(u64, u64)> {
+        assert!(buffer_size > 0, "buffer_size must be positive");
+        let mut buffer = vec![0; buffer_size];
+        let mut hash = Modular::from_u64(0);
+        let mut length = 0u64;
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(bytes = length, "hashed");
+                    return Ok((hash.value(), length));
+                }
+                Ok(read) => {
+                    hash = bulk::extend(hash, &buffer[..read]);
+                    length += read as u64;
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::RollingHash;
+
+    #[test]
+    fn hash_reader_matches_from_initial_bytes() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i * 7) as u8).collect();
+        let expected = RollingHash::from_initial_bytes(&data).get_current_hash();
+        for buffer_size in [1, 3, 4096, 100_000] {
+            let hashed = RollingHash::hash_reader_with_buffer_size(Cursor::new(&data), buffer_size);
+            assert_eq!(hashed.unwrap(), (expected, 10_000));
+        }
+    }
+
+    #[test]
+    fn hash_file_reads_from_disk() {
+        let path = std::env::temp_dir().join(format!("rolling_hash_{}.txt", std::process::id()));
+        std::fs::write(&path, b"Eiger").unwrap();
+        let hashed = RollingHash::hash_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let expected = RollingHash::from_initial_bytes(b"Eiger").get_current_hash();
+        assert_eq!(hashed.unwrap(), (expected, 5));
+        assert!(RollingHash::hash_file(&path).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use crate::modular::Modular;
+use crate::{bulk, Numeric, RollingHash};
+
+// A window of exactly W bytes. It starts full and the only way to change it is
+// roll(), which pushes one byte and pops another, so the length can never drift.
+// The bytes live in an inline ring buffer, and the single power rolling needs,
+// BASE^(W-1), is computed once: no power table, no allocation
+#[derive(Debug, Clone)]
+pub struct RollingWindowHash<const W: usize> {
+    bytes: [u8; W],
+    // Index of the oldest byte in `bytes`
+    head: usize,
+    hash: Numeric,
+    leaving_power: Numeric,
+}
+
+// Same bytes in the same order, wherever the ring buffer happens to start
+impl<const W: usize> PartialEq for RollingWindowHash<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.get_current_bytes() == other.get_current_bytes()
+    }
+}
+
+impl<const W: usize> Eq for RollingWindowHash<W> {}
+
+impl<const W: usize> RollingWindowHash<W> {
+    pub fn new(initial: [u8; W]) -> Self {
+        const { assert!(W > 0, "the window must not be empty") };
+        Self {
+            bytes: initial,
+            head: 0,
+            hash: bulk::extend(Modular::from_u64(0), &initial),
+            leaving_power: Modular::from_u64(RollingHash::BASE).pow(W as u64 - 1),
+        }
+    }
+
+    // None unless `initial` has exactly W bytes
+    pub fn from_slice(initial: &[u8]) -> Option<Self> {
+        initial.try_into().ok().map(Self::new)
+    }
+
+    // Appends `byte` and returns the byte that fell out of the front
+    pub fn roll(&mut self, byte: u8) -> u8 {
+        let leaving = std::mem::replace(&mut self.bytes[self.head], byte);
+        self.head = (self.head + 1) % W;
+        self.hash =
+            (self.hash - self.leaving_power * leaving as u64) * RollingHash::BASE + byte as u64;
+        leaving
+    }
+
+    pub fn get_current_hash(&self) -> u64 {
+        self.hash.value()
+    }
+
+    // In window order, oldest first
+    pub fn get_current_bytes(&self) -> [u8; W] {
+        let mut bytes = self.bytes;
+        bytes.rotate_left(self.head);
+        bytes
+    }
+
+    pub const fn len(&self) -> usize {
+        W
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+// Up to N bytes, in the same kind of inline ring buffer as RollingWindowHash, for
+// windows that fill up gradually or shrink again, e.g. matching frames of
+// varying length on a device without a heap. Once full, push_back() evicts the
+// oldest byte. The powers of BASE up to N are computed once, inline as well
+#[derive(Debug, Clone)]
+pub struct BoundedWindowHash<const N: usize> {
+    bytes: [u8; N],
+    // Index of the oldest byte in `bytes`
+    head: usize,
+    len: usize,
+    hash: Numeric,
+    // powers[i] = BASE^i
+    powers: [Numeric; N],
+}
+
+impl<const N: usize> PartialEq for BoundedWindowHash<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<const N: usize> Eq for BoundedWindowHash<N> {}
+
+impl<const N: usize> Default for BoundedWindowHash<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> BoundedWindowHash<N> {
+    pub fn new() -> Self {
+        const { assert!(N > 0, "the window must not be empty") };
+        let mut powers = [Modular::from_u64(1); N];
+        for i in 1..N {
+            powers[i] = powers[i - 1] * RollingHash::BASE;
+        }
+        Self {
+            bytes: [0; N],
+            head: 0,
+            len: 0,
+            hash: Modular::from_u64(0),
+            powers,
+        }
+    }
+
+    // Returns the byte evicted to make room, if the window was full
+    pub fn push_back(&mut self, byte: u8) -> Option<u8> {
+        let evicted = if self.len == N {
+            self.pop_front()
+        } else {
+            None
+        };
+        self.bytes[(self.head + self.len) % N] = byte;
+        self.len += 1;
+        self.hash = self.hash * RollingHash::BASE + byte as u64;
+        evicted
+    }
+
+    pub fn pop_front(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+        let leaving = self.bytes[self.head];
+        self.hash = self.hash - self.powers[self.len - 1] * leaving as u64;
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(leaving)
+    }
+
+    pub fn reset(&mut self) {
+        self.head = 0;
+        self.len = 0;
+        self.hash = Modular::from_u64(0);
+    }
+
+    pub fn get_current_hash(&self) -> u64 {
+        self.hash.value()
+    }
+
+    // In window order, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..self.len).map(|i| self.bytes[(self.head + i) % N])
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::fixed_window::{BoundedWindowHash, RollingWindowHash};
+    use crate::RollingHash;
+
+    #[test]
+    fn roll_matches_rolling_hash() {
+        let mut window = RollingWindowHash::new(*b"Eige");
+        assert_eq!(window.roll(b'r'), b'E');
+        assert_eq!(window.get_current_bytes(), *b"iger");
+        assert_eq!(
+            window.get_current_hash(),
+            RollingHash::from_initial_bytes(b"iger").get_current_hash()
+        );
+    }
+
+    #[test]
+    fn equality_ignores_the_ring_layout() {
+        let mut rolled = RollingWindowHash::new(*b"Eige");
+        rolled.roll(b'r');
+        assert_eq!(rolled, RollingWindowHash::new(*b"iger"));
+    }
+
+    #[test]
+    fn from_slice_checks_the_length() {
+        assert!(RollingWindowHash::<5>::from_slice(b"Eiger").is_some());
+        assert!(RollingWindowHash::<5>::from_slice(b"Eige").is_none());
+    }
+
+    #[test]
+    fn bounded_windows_fill_up_and_evict() {
+        let mut window = BoundedWindowHash::<4>::new();
+        for byte in *b"Eig" {
+            assert_eq!(window.push_back(byte), None);
+        }
+        assert!(!window.is_full());
+        assert_eq!(window.push_back(b'e'), None);
+        assert_eq!(window.push_back(b'r'), Some(b'E'));
+        assert_eq!(window.iter().collect::<Vec<_>>(), b"iger");
+        assert_eq!(
+            window.get_current_hash(),
+            RollingHash::from_initial_bytes(b"iger").get_current_hash()
+        );
+        assert_eq!(window.pop_front(), Some(b'i'));
+        assert_eq!(window.capacity(), 4);
+        assert_eq!(window.len(), 3);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_a_bounded_rolling_hash(
+            initial in any::<[u8; 7]>(),
+            rolled in prop::collection::vec(any::<u8>(), 0..50),
+        ) {
+            let mut window = RollingWindowHash::new(initial);
+            let mut rh = RollingHash::with_max_window(7);
+            rh.extend_from_slice(&initial);
+            for byte in rolled {
+                window.roll(byte);
+                rh.push_back(byte);
+                prop_assert_eq!(window.get_current_hash(), rh.get_current_hash());
+            }
+            prop_assert_eq!(window.get_current_bytes().to_vec(), rh.get_current_bytes());
+        }
+
+        #[test]
+        fn bounded_matches_a_bounded_rolling_hash(
+            operations in prop::collection::vec(prop::option::of(any::<u8>()), 0..100),
+        ) {
+            // Some(byte) pushes it, None pops the front
+            let mut window = BoundedWindowHash::<7>::new();
+            let mut rh = RollingHash::with_max_window(7);
+            for operation in operations {
+                match operation {
+                    Some(byte) => {
+                        window.push_back(byte);
+                        rh.push_back(byte);
+                    }
+                    None => {
+                        window.pop_front();
+                        rh.pop_front();
+                    }
+                }
+                prop_assert_eq!(window.get_current_hash(), rh.get_current_hash());
+            }
+            prop_assert_eq!(window.iter().collect::<Vec<_>>(), rh.get_current_bytes());
+        }
+    }
+}
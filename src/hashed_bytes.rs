@@ -0,0 +1,109 @@
+use std::hash::{Hash, Hasher};
+
+use crate::RollingHash;
+
+// Owned bytes together with their rolling hash, usable as a HashMap key.
+// Comparisons look at the hash and length first, and only compare the bytes
+// when those match, so a hash collision never makes two keys equal
+#[derive(Debug, Clone)]
+pub struct HashedBytes {
+    bytes: Vec<u8>,
+    hash: u64,
+}
+
+impl HashedBytes {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        let hash = RollingHash::from_initial_bytes(&bytes).get_current_hash();
+        Self { bytes, hash }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn rolling_hash(&self) -> u64 {
+        self.hash
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl From<Vec<u8>> for HashedBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl From<&[u8]> for HashedBytes {
+    fn from(bytes: &[u8]) -> Self {
+        Self::new(bytes.to_vec())
+    }
+}
+
+impl PartialEq for HashedBytes {
+    fn eq(&self, other: &Self) -> bool {
+        // Cheap checks first, the byte comparison only runs on a likely match
+        self.hash == other.hash && self.len() == other.len() && self.bytes == other.bytes
+    }
+}
+
+impl Eq for HashedBytes {}
+
+impl Hash for HashedBytes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+        state.write_usize(self.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::hashed_bytes::HashedBytes;
+    use crate::RollingHash;
+
+    #[test]
+    fn equal_bytes_are_equal() {
+        let a = HashedBytes::from(b"Eiger".as_slice());
+        let b = HashedBytes::new(b"Eiger".to_vec());
+        assert_eq!(a, b);
+        assert_eq!(
+            a.rolling_hash(),
+            RollingHash::from_initial_bytes(b"Eiger").get_current_hash()
+        );
+    }
+
+    #[test]
+    fn colliding_bytes_are_not_equal() {
+        // Known collision, see `hash_collision_example` in lib.rs
+        let s1 = b"ryIqVm6i3M25uvTttp2Qo8mlkWmKap5PkuWHtS3AZZkRBWCAE9jGCWpkgYHaQobJDJrhdwdoNRGjqQmaTAi5ZGo6hbslnzIL2HaP";
+        let s2 = b"eVCblKi7jexBFHudJsTfj8ibzxgXGlol8EthCd8OBniEXI6tVR9LFkNzPtNeqR3EIVERZwtG1uxFimT3cPQAHwTTiuRnj6gHh406";
+        let a = HashedBytes::from(s1.as_slice());
+        let b = HashedBytes::from(s2.as_slice());
+        assert_eq!(a.rolling_hash(), b.rolling_hash());
+        assert_ne!(a, b);
+
+        let set: HashSet<HashedBytes> = [a, b].into_iter().collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn works_as_a_set_key() {
+        let mut set = HashSet::new();
+        assert!(set.insert(HashedBytes::from(b"Eiger".as_slice())));
+        assert!(set.insert(HashedBytes::from(b"Tiger".as_slice())));
+        assert!(!set.insert(HashedBytes::from(b"Eiger".as_slice())));
+        assert!(set.contains(&HashedBytes::from(b"Tiger".as_slice())));
+    }
+}
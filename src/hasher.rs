@@ -0,0 +1,333 @@
+use std::collections::VecDeque;
+
+use crate::RollingHash;
+
+// What higher-level code needs from a rolling hash backend: a window of bytes that
+// grows at the back, shrinks at the front, and can be digested at any point
+pub trait RollingHasher {
+    // Back to the empty window, keeping any configuration
+    fn reset(&mut self);
+
+    fn push_back(&mut self, b: u8);
+
+    // Does nothing on an empty window
+    fn pop_front(&mut self);
+
+    // Slides the window by one byte
+    fn roll(&mut self, b: u8) {
+        self.pop_front();
+        self.push_back(b);
+    }
+
+    fn digest(&self) -> u64;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Identifies the backend, so persisted digests can record what produced them
+    fn algorithm_id(&self) -> &'static str;
+}
+
+impl RollingHasher for RollingHash {
+    fn reset(&mut self) {
+        RollingHash::reset(self);
+    }
+
+    fn push_back(&mut self, b: u8) {
+        RollingHash::push_back(self, b);
+    }
+
+    fn pop_front(&mut self) {
+        RollingHash::pop_front(self);
+    }
+
+    fn digest(&self) -> u64 {
+        self.get_current_hash()
+    }
+
+    fn len(&self) -> usize {
+        RollingHash::len(self)
+    }
+
+    fn algorithm_id(&self) -> &'static str {
+        RollingHash::algorithm_id(self)
+    }
+}
+
+// Buzhash (cyclic polynomial): every byte maps to a fixed random 64-bit value,
+// and the digest of b_0 .. b_{n-1} is rol^(n-1)(T[b_0]) ^ ... ^ rol^0(T[b_{n-1}]).
+// Rolling is rotations and XORs only, with no modulus
+#[derive(Debug, Clone, Default)]
+pub struct Buzhash {
+    bytes: VecDeque<u8>,
+    hash: u64,
+}
+
+// A value per byte from splitmix64, so digests are reproducible
+const fn random_table(seed: u64) -> [u64; 256] {
+    let mut table = [0; 256];
+    let mut state = seed;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+const BUZHASH_TABLE: [u64; 256] = random_table(0x6275_7a68_6173_6800);
+
+impl Buzhash {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RollingHasher for Buzhash {
+    fn reset(&mut self) {
+        self.bytes.clear();
+        self.hash = 0;
+    }
+
+    fn push_back(&mut self, b: u8) {
+        self.bytes.push_back(b);
+        self.hash = self.hash.rotate_left(1) ^ BUZHASH_TABLE[b as usize];
+    }
+
+    fn pop_front(&mut self) {
+        // The front byte has been rotated once for every byte after it
+        let rotation = (self.bytes.len().saturating_sub(1) % 64) as u32;
+        if let Some(b) = self.bytes.pop_front() {
+            self.hash ^= BUZHASH_TABLE[b as usize].rotate_left(rotation);
+        }
+    }
+
+    fn digest(&self) -> u64 {
+        self.hash
+    }
+
+    fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn algorithm_id(&self) -> &'static str {
+        "buzhash"
+    }
+}
+
+// Gear hash, as in FastCDC: the digest of b_0 .. b_{n-1} is
+// sum(G[b_i] << (n-1-i)) mod 2^64, so only the last 64 bytes affect it.
+// Pushing a byte is a shift and an add
+#[derive(Debug, Clone, Default)]
+pub struct Gear {
+    bytes: VecDeque<u8>,
+    hash: u64,
+}
+
+const GEAR_TABLE: [u64; 256] = random_table(0x6765_6172_0000_0000);
+
+impl Gear {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RollingHasher for Gear {
+    fn reset(&mut self) {
+        self.bytes.clear();
+        self.hash = 0;
+    }
+
+    fn push_back(&mut self, b: u8) {
+        self.bytes.push_back(b);
+        self.hash = (self.hash << 1).wrapping_add(GEAR_TABLE[b as usize]);
+    }
+
+    fn pop_front(&mut self) {
+        // Its term has already been shifted out if 64 or more bytes follow it
+        let shift = self.bytes.len().saturating_sub(1) as u32;
+        if let Some(b) = self.bytes.pop_front() {
+            let term = GEAR_TABLE[b as usize].checked_shl(shift).unwrap_or(0);
+            self.hash = self.hash.wrapping_sub(term);
+        }
+    }
+
+    fn digest(&self) -> u64 {
+        self.hash
+    }
+
+    fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn algorithm_id(&self) -> &'static str {
+        "gear"
+    }
+}
+
+// The Adler-32 checksum (as in zlib), the weak checksum rsync's is modelled on:
+// a = 1 + sum(b_i) and b = n + sum((n-i) * b_i), both mod 65521, with digest
+// b << 16 | a. Cheap, but a poor hash for short windows
+#[derive(Debug, Clone)]
+pub struct Adler32 {
+    bytes: VecDeque<u8>,
+    a: u64,
+    b: u64,
+}
+
+const ADLER_MODULUS: u64 = 65521;
+
+impl Default for Adler32 {
+    fn default() -> Self {
+        Self {
+            bytes: VecDeque::new(),
+            a: 1,
+            b: 0,
+        }
+    }
+}
+
+impl Adler32 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RollingHasher for Adler32 {
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn push_back(&mut self, b: u8) {
+        self.bytes.push_back(b);
+        self.a = (self.a + b as u64) % ADLER_MODULUS;
+        self.b = (self.b + self.a) % ADLER_MODULUS;
+    }
+
+    fn pop_front(&mut self) {
+        let len = self.bytes.len() as u64;
+        if let Some(b) = self.bytes.pop_front() {
+            // The byte was counted once in a, and once per byte from it on in b,
+            // along with the initial 1
+            let removed_from_b = (len % ADLER_MODULUS * b as u64 + 1) % ADLER_MODULUS;
+            self.a = (self.a + ADLER_MODULUS - b as u64) % ADLER_MODULUS;
+            self.b = (self.b + ADLER_MODULUS - removed_from_b) % ADLER_MODULUS;
+        }
+    }
+
+    fn digest(&self) -> u64 {
+        self.b << 16 | self.a
+    }
+
+    fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn algorithm_id(&self) -> &'static str {
+        "adler32"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::hasher::{Adler32, Buzhash, Gear, RollingHasher};
+    use crate::RollingHash;
+
+    // Written once against the trait, as higher-level code would be
+    fn window_digests(hasher: &mut impl RollingHasher, data: &[u8], window: usize) -> Vec<u64> {
+        hasher.reset();
+        let mut digests = Vec::new();
+        for &b in data {
+            if hasher.len() == window {
+                hasher.roll(b);
+            } else {
+                hasher.push_back(b);
+            }
+            if hasher.len() == window {
+                digests.push(hasher.digest());
+            }
+        }
+        digests
+    }
+
+    #[test]
+    fn polynomial_hash_through_the_trait() {
+        let mut rh = RollingHash::from_initial_bytes(b"leftovers");
+        let digests = window_digests(&mut rh, b"Eiger", 3);
+        let expected: Vec<u64> = [b"Eig", b"ige", b"ger"]
+            .iter()
+            .map(|w| RollingHash::from_initial_bytes(*w).get_current_hash())
+            .collect();
+        assert_eq!(digests, expected);
+    }
+
+    // Holds for any backend: rolling over a window gives the digest of hashing
+    // that window from scratch
+    fn rolling_matches_rehashing<H: RollingHasher>(
+        mut fresh: impl FnMut() -> H,
+        data: &[u8],
+        window: usize,
+    ) -> Result<(), TestCaseError> {
+        let digests = window_digests(&mut fresh(), data, window);
+        let expected: Vec<u64> = data
+            .windows(window)
+            .map(|w| {
+                let mut hasher = fresh();
+                w.iter().for_each(|&b| hasher.push_back(b));
+                hasher.digest()
+            })
+            .collect();
+        prop_assert_eq!(digests, expected);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn every_backend_rolls_consistently(
+            data in prop::collection::vec(any::<u8>(), 0..200),
+            window in 1..100usize,
+        ) {
+            rolling_matches_rehashing(RollingHash::new, &data, window)?;
+            rolling_matches_rehashing(Buzhash::new, &data, window)?;
+            rolling_matches_rehashing(Gear::new, &data, window)?;
+            rolling_matches_rehashing(Adler32::new, &data, window)?;
+        }
+    }
+
+    #[test]
+    fn backends_have_distinct_ids() {
+        let mut ids = vec![
+            RollingHash::new().algorithm_id(),
+            Buzhash::new().algorithm_id(),
+            Gear::new().algorithm_id(),
+            Adler32::new().algorithm_id(),
+        ];
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 4);
+    }
+
+    #[test]
+    fn adler32_matches_zlib() {
+        let mut adler = Adler32::new();
+        b"Wikipedia".iter().for_each(|&b| adler.push_back(b));
+        assert_eq!(adler.digest(), 0x11e6_0398);
+    }
+
+    #[test]
+    fn reset_empties_the_window() {
+        let mut rh = RollingHash::from_initial_bytes(b"Eiger");
+        RollingHasher::reset(&mut rh);
+        assert!(RollingHasher::is_empty(&rh));
+        assert_eq!(rh.digest(), RollingHash::new().get_current_hash());
+    }
+}
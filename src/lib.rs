@@ -1,17 +1,122 @@
 use std::collections::VecDeque;
+use std::ops::Range;
 
+pub use error::{Error, Result};
 use modular::Modular;
+use powers::PowerTable;
 
-mod modular;
+pub mod alphabet;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+pub mod build_hasher;
+mod bulk;
+pub mod compare;
+pub mod dedup;
+#[cfg(feature = "digest")]
+mod digest_traits;
+mod error;
+pub mod fenwick;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod file;
+pub mod fixed_window;
+pub mod hashed_bytes;
+pub mod hasher;
+pub mod matrix;
+pub mod merkle;
+pub mod minimizers;
+mod mismatch;
+pub mod modular;
+pub mod nthash;
+pub mod operation;
+pub mod params;
+pub mod persistent;
+mod powers;
+pub mod progress;
+#[cfg(feature = "python")]
+mod python;
+pub mod rope;
+pub mod segment_tree;
+pub mod shingling;
+pub mod similarity;
+pub mod sliding_window;
+pub mod stream;
+#[cfg(any(feature = "blake3", feature = "sha256"))]
+pub mod strong;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod windows;
+pub mod winnowing;
 
 const BIG_PRIME: u64 = 1_000_000_007;
 
+// Drawn on first use, then the same for the rest of the process
+fn process_base() -> u64 {
+    use rand::Rng;
+    static PROCESS_BASE: std::sync::OnceLock<u64> = std::sync::OnceLock::new();
+    // Above 256, so every byte value is a distinct digit
+    *PROCESS_BASE.get_or_init(|| rand::rngs::OsRng.gen_range(257..BIG_PRIME))
+}
+
 type Numeric = Modular<BIG_PRIME>;
 
+// The hash of bytes b_0 .. b_{n-1} is sum(b_i * BASE^(n-1-i)) mod BIG_PRIME.
+// It is anchored at the back: the last byte always has exponent 0, so the powers
+// we need only depend on the current length, never on how many bytes have flowed
+// through. A stream of any length hashed through a window of length W only ever
+// needs BASE^0 ..= BASE^W (and with_max_window() makes that bound explicit)
+#[derive(Clone)]
 pub struct RollingHash {
     current_bytes: VecDeque<u8>,
     current_hash: Numeric,
-    base_powers: Vec<Numeric>,
+    // BASE unless set_base() or randomize_base() picked another one
+    base: u64,
+    base_powers: PowerTable,
+    // BASE^-1, so that pop_back() multiplies instead of dividing, as
+    // every modular division costs a full fast exponentiation
+    base_inverse: Numeric,
+    // In bounded mode, pushing onto a full window evicts from the opposite end
+    max_window: Option<usize>,
+    // Hash of the bytes in reverse order, sum(b_i * BASE^i), only kept up to
+    // date once track_reversed_hash() has been called
+    reversed_hash: Option<Numeric>,
+    // Applied to every incoming byte, see set_byte_map()
+    byte_map: Option<fn(u8) -> u8>,
+}
+
+// Two hashers are equal when they hold the same bytes, hashed with the same base.
+// The hash and length are compared first, so the byte comparison only runs on a
+// likely match
+impl PartialEq for RollingHash {
+    fn eq(&self, other: &Self) -> bool {
+        self.current_hash == other.current_hash
+            && self.base == other.base
+            && self.current_bytes.len() == other.current_bytes.len()
+            && self.current_bytes == other.current_bytes
+    }
+}
+
+impl Eq for RollingHash {}
+
+// The bytes and their hash; the power table is just a cache
+impl std::fmt::Debug for RollingHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RollingHash")
+            .field("bytes", &self.current_bytes)
+            .field("hash", &self.current_hash.value)
+            .field("base", &self.base)
+            .field("max_window", &self.max_window)
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::hash::Hash for RollingHash {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.current_hash.value);
+        state.write_usize(self.current_bytes.len());
+    }
 }
 
 impl Default for RollingHash {
@@ -20,6 +125,24 @@ impl Default for RollingHash {
     }
 }
 
+// rh[0] is the front byte. Panics if out of bounds, see get() for a checked version
+impl std::ops::Index<usize> for RollingHash {
+    type Output = u8;
+
+    fn index(&self, index: usize) -> &u8 {
+        &self.current_bytes[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a RollingHash {
+    type Item = &'a u8;
+    type IntoIter = std::collections::vec_deque::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl RollingHash {
     const BASE: u64 = 257;
 
@@ -27,13 +150,47 @@ impl RollingHash {
         Self {
             current_bytes: VecDeque::new(),
             current_hash: Modular::from_u64(0),
-            base_powers: vec![Modular::from_u64(1)],
+            base: Self::BASE,
+            base_powers: PowerTable::new(),
+            // BIG_PRIME is prime and BASE is smaller, so the inverse always exists
+            base_inverse: Modular::from_u64(Self::BASE)
+                .try_inverse()
+                .expect("BASE must be coprime with BIG_PRIME"),
+            max_window: None,
+            reversed_hash: None,
+            byte_map: None,
         }
     }
 
+    // Reserves room for `capacity` bytes up front, so hot loops that stay
+    // within it never reallocate
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut rh = Self::new();
+        rh.current_bytes = VecDeque::with_capacity(capacity);
+        rh.base_powers.ensure(capacity);
+        rh
+    }
+
+    // Keeps at most `max_window` bytes: once full, push_back() drops the front byte and
+    // push_front() drops the back byte, so memory stays bounded by the window size
+    pub fn with_max_window(max_window: usize) -> Self {
+        assert!(max_window > 0, "max_window must be positive");
+        let mut rh = Self::with_capacity(max_window);
+        rh.max_window = Some(max_window);
+        rh
+    }
+
+    pub fn max_window(&self) -> Option<usize> {
+        self.max_window
+    }
+
+    fn is_full(&self) -> bool {
+        self.max_window == Some(self.current_bytes.len())
+    }
+
     pub fn from_initial_bytes(input: &[u8]) -> Self {
         let mut rh = Self::new();
-        input.iter().for_each(|&c| rh.push_back(c));
+        rh.extend_from_slice(input);
         rh
     }
 
@@ -42,14 +199,164 @@ impl RollingHash {
         self.current_bytes.clone().into()
     }
 
+    // The window without copying it, like VecDeque::as_slices(): the bytes are
+    // the first slice followed by the second, which is empty unless the ring
+    // buffer wraps around
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
+        self.current_bytes.as_slices()
+    }
+
+    // The byte at `index` (counting from the front), if any
+    pub fn get(&self, index: usize) -> Option<u8> {
+        self.current_bytes.get(index).copied()
+    }
+
+    // The bytes of the window, front to back, without copying them
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, u8> {
+        self.current_bytes.iter()
+    }
+
     pub fn get_current_hash(&self) -> u64 {
         self.current_hash.value
     }
 
+    // An independent copy to explore another continuation with. The power table
+    // is shared rather than copied, so this only costs a copy of the window
+    pub fn fork(&self) -> Self {
+        self.clone()
+    }
+
+    // The parameters that produced the hashes, for tools that persist them
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    // Hashes from now on with `base` instead of BASE, rehashing the bytes already
+    // in the window. Any 1 < base < modulus works, and hashes are reproducible
+    // for the same base
+    pub fn set_base(&mut self, base: u64) {
+        assert!(
+            base > 1 && base < BIG_PRIME,
+            "base must be between 2 and {}",
+            BIG_PRIME - 1
+        );
+        self.base = base;
+        self.base_powers = PowerTable::with_base(base);
+        self.update_base_powers();
+        // The modulus is prime, so every base in range has an inverse
+        self.base_inverse = Modular::from_u64(base)
+            .try_inverse()
+            .expect("base must be coprime with BIG_PRIME");
+        let bytes = self.current_bytes.make_contiguous();
+        self.current_hash = bytes
+            .iter()
+            .fold(Modular::from_u64(0), |hash, &b| hash * base + b as u64);
+        if self.reversed_hash.is_some() {
+            self.reversed_hash = None;
+            self.track_reversed_hash();
+        }
+    }
+
+    // Switches to a profile, e.g. one found with params::by_name(). Panics if its
+    // modulus is not ours
+    pub fn set_params(&mut self, params: &params::HashParams) {
+        assert_eq!(
+            params.modulus, BIG_PRIME,
+            "profile {} has another modulus",
+            params.name
+        );
+        self.set_base(params.base);
+    }
+
+    // Like set_base(), with a base drawn from OS entropy once per process, in the
+    // spirit of std's RandomState: whoever crafts colliding inputs for the fixed
+    // BASE (or for any base they can guess) gets nowhere. Hashes then differ
+    // between runs, so they must not be persisted
+    pub fn randomize_base(&mut self) {
+        self.set_base(process_base());
+    }
+
+    pub fn modulus(&self) -> u64 {
+        BIG_PRIME
+    }
+
+    pub fn algorithm_id(&self) -> &'static str {
+        "polynomial"
+    }
+
+    // Bases without a named profile get a name of the same form, so data hashed
+    // with one base never passes check_name() for another
+    pub fn params(&self) -> params::HashParams {
+        if let Some(params) = params::ALL
+            .into_iter()
+            .find(|params| params.base == self.base)
+        {
+            return params;
+        }
+        params::HashParams {
+            name: format!("1e9+7/{}", self.base).into(),
+            base: self.base,
+            modulus: BIG_PRIME,
+        }
+    }
+
+    // Empties the window, but keeps the configuration (max window, reversed hash, base)
+    pub fn reset(&mut self) {
+        self.current_bytes.clear();
+        self.current_hash = Modular::from_u64(0);
+        if self.reversed_hash.is_some() {
+            self.reversed_hash = Some(Modular::from_u64(0));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.current_bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current_bytes.is_empty()
+    }
+
+    // Starts maintaining the hash of the reversed window, which every operation
+    // then updates alongside the forward hash
+    pub fn track_reversed_hash(&mut self) {
+        let reversed = self
+            .current_bytes
+            .iter()
+            .rev()
+            .fold(Modular::from_u64(0), |hash, &b| hash * self.base + b as u64);
+        self.reversed_hash = Some(reversed);
+    }
+
+    // Normalizes bytes on their way in (see the alphabet module for common maps), so
+    // the input does not need a transformed copy. Bytes already in the window are
+    // kept as they are, so set it before pushing anything
+    pub fn set_byte_map(&mut self, map: fn(u8) -> u8) {
+        self.byte_map = Some(map);
+    }
+
+    fn map_byte(&self, b: u8) -> u8 {
+        self.byte_map.map_or(b, |map| map(b))
+    }
+
+    // None unless track_reversed_hash() was called
+    pub fn get_reversed_hash(&self) -> Option<u64> {
+        self.reversed_hash.map(|hash| hash.value)
+    }
+
     pub fn push_back(&mut self, b: u8) {
+        let b = self.map_byte(b);
+        if self.is_full() {
+            self.pop_front();
+        }
+        if let Some(reversed) = &mut self.reversed_hash {
+            // The new byte is the highest term of the reversed hash
+            let factor = self.base_powers.get(self.current_bytes.len());
+            *reversed = *reversed + factor * b as u64;
+        }
         self.current_bytes.push_back(b);
 
-        self.current_hash = self.current_hash * Self::BASE;
+        self.current_hash = self.current_hash * self.base;
         self.current_hash = self.current_hash + (b as u64);
 
         // After we have added a byte, we may need to update our
@@ -57,19 +364,27 @@ impl RollingHash {
         self.update_base_powers();
     }
 
+    // Same as calling push_back() for every byte, but hashes many bytes at a time
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        if self.max_window.is_some()
+            || self.reversed_hash.is_some()
+            || self.byte_map.is_some()
+            || self.base != Self::BASE
+        {
+            // Bytes may need to be evicted or mapped along the way, and the
+            // reversed hash and other bases have no bulk update
+            bytes.iter().for_each(|&b| self.push_back(b));
+            return;
+        }
+
+        self.current_hash = bulk::extend(self.current_hash, bytes);
+        self.current_bytes.extend(bytes);
+        self.update_base_powers();
+    }
+
     fn update_base_powers(&mut self) {
         // At most, we will need to use BASE^len, where len is the length of the string
-        let current_content_len = self.current_bytes.len();
-        let current_base_powers_len = self.base_powers.len();
-        if current_content_len >= current_base_powers_len {
-            let needed = current_content_len - current_base_powers_len + 1;
-            for _ in 0..needed {
-                // We have constructed it with one value, and we never remove values
-                let &last_power = self.base_powers.last().unwrap();
-                let next_power = last_power * Self::BASE;
-                self.base_powers.push(next_power);
-            }
-        }
+        self.base_powers.ensure(self.current_bytes.len());
     }
 
     pub fn pop_front(&mut self) {
@@ -78,9 +393,12 @@ impl RollingHash {
             let len = self.current_bytes.len();
             // We maintain base_powers always updated, so we should
             // always have this value here
-            let factor = self.base_powers[len - 1];
+            let factor = self.base_powers.get(len - 1);
             let contribution = factor * front_byte as u64;
             self.current_hash = self.current_hash - contribution;
+            if let Some(reversed) = &mut self.reversed_hash {
+                *reversed = (*reversed - front_byte as u64) * self.base_inverse;
+            }
             self.current_bytes.pop_front();
         }
     }
@@ -93,18 +411,201 @@ impl RollingHash {
             self.current_hash = self.current_hash - contribution;
 
             // And now we need to "shift" the previous bytes, regarding the exponents
-            self.current_hash = self.current_hash / Self::BASE;
+            self.current_hash = self.current_hash * self.base_inverse;
+            if let Some(reversed) = &mut self.reversed_hash {
+                let len = self.current_bytes.len();
+                *reversed = *reversed - self.base_powers.get(len - 1) * back_byte as u64;
+            }
             self.current_bytes.pop_back();
         }
     }
 
+    // Keeps the first `len` bytes, like Vec::truncate(). The removed bytes are
+    // hashed in bulk and subtracted, and the rest is shifted down with a single
+    // multiplication by BASE^-removed, instead of one pop_back() per byte
+    pub fn truncate_back(&mut self, len: usize) {
+        let removed = self.current_bytes.len().saturating_sub(len);
+        if removed == 0 {
+            return;
+        }
+        if self.reversed_hash.is_some() {
+            // The reversed hash has no bulk update
+            (0..removed).for_each(|_| self.pop_back());
+            return;
+        }
+        let suffix = self.hash_of_range(len..self.current_bytes.len());
+        self.current_hash = (self.current_hash - suffix) * self.base_inverse.pow(removed as u64);
+        self.current_bytes.truncate(len);
+    }
+
+    // Keeps the last `n` bytes. The removed bytes are hashed in bulk and their
+    // weight taken off with one BASE^n from the power table
+    pub fn keep_last(&mut self, n: usize) {
+        let removed = self.current_bytes.len().saturating_sub(n);
+        if removed == 0 {
+            return;
+        }
+        if self.reversed_hash.is_some() {
+            (0..removed).for_each(|_| self.pop_front());
+            return;
+        }
+        let prefix = self.hash_of_range(0..removed);
+        self.current_hash = self.current_hash - prefix * self.base_powers.get(n);
+        self.current_bytes.drain(..removed);
+    }
+
+    // Removes up to `n` bytes from the front and returns them, e.g. to forward
+    // evicted data elsewhere. The hash is updated as in keep_last()
+    pub fn drain_front(&mut self, n: usize) -> Vec<u8> {
+        let n = n.min(self.current_bytes.len());
+        let drained = self.current_bytes.range(..n).copied().collect();
+        self.keep_last(self.current_bytes.len() - n);
+        drained
+    }
+
+    // Like VecDeque::split_off(): `self` keeps bytes[..at] and the returned hasher,
+    // configured like `self`, gets bytes[at..]. Only the tail is hashed, and the
+    // head's hash follows from it. Panics if `at` is out of bounds
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.current_bytes.len();
+        assert!(at <= len, "`at` {at} out of bounds for length {len}");
+        let tail_hash = self.hash_of_range(at..len);
+        let mut tail = Self {
+            current_bytes: self.current_bytes.split_off(at),
+            current_hash: tail_hash,
+            base: self.base,
+            base_powers: self.base_powers.clone(),
+            base_inverse: self.base_inverse,
+            max_window: self.max_window,
+            reversed_hash: None,
+            byte_map: self.byte_map,
+        };
+        self.current_hash =
+            (self.current_hash - tail_hash) * self.base_inverse.pow((len - at) as u64);
+        if let Some(reversed) = self.reversed_hash {
+            // The tail's bytes had weights BASE^at and up in our reversed hash
+            tail.track_reversed_hash();
+            let tail_reversed = tail.reversed_hash.expect("just started tracking");
+            self.reversed_hash = Some(reversed - tail_reversed * self.base_powers.get(at));
+        }
+        tail
+    }
+
+    // Moves all of other's bytes onto our back and leaves `other` empty, like
+    // VecDeque::append(). The hashes combine as hash * BASE^other.len() + other's
+    // hash, so nothing is rehashed. With a byte map, a window bound that would
+    // evict, a different base or the reversed hash, the bytes are pushed one by
+    // one instead
+    pub fn append(&mut self, other: &mut Self) {
+        let total = self.current_bytes.len() + other.current_bytes.len();
+        let fits = self.max_window.is_none_or(|max| total <= max);
+        if !fits
+            || self.base != other.base
+            || self.byte_map.is_some()
+            || self.reversed_hash.is_some()
+        {
+            other
+                .current_bytes
+                .drain(..)
+                .for_each(|b| self.push_back(b));
+            other.reset();
+            return;
+        }
+        self.base_powers.ensure(total);
+        let shift = self.base_powers.get(other.current_bytes.len());
+        self.current_hash = self.current_hash * shift + other.current_hash;
+        self.current_bytes.append(&mut other.current_bytes);
+        other.reset();
+    }
+
+    // The hash of current_bytes[range] on its own
+    fn hash_of_range(&self, range: Range<usize>) -> Numeric {
+        let (front, back) = self.current_bytes.as_slices();
+        let split = front.len();
+        let in_front = &front[range.start.min(split)..range.end.min(split)];
+        let in_back = &back[range.start.saturating_sub(split)..range.end.saturating_sub(split)];
+        let hash = self.extend_hash(Modular::from_u64(0), in_front);
+        self.extend_hash(hash, in_back)
+    }
+
+    // Appends `bytes` to `hash` with this hasher's base
+    fn extend_hash(&self, hash: Numeric, bytes: &[u8]) -> Numeric {
+        if self.base == Self::BASE {
+            return bulk::extend(hash, bytes);
+        }
+        bytes
+            .iter()
+            .fold(hash, |hash, &b| hash * self.base + b as u64)
+    }
+
+    // Replaces the byte at `index` (counting from the front)
+    pub fn set_byte(&mut self, index: usize, new_byte: u8) {
+        let len = self.current_bytes.len();
+        assert!(index < len, "index {index} out of bounds for length {len}");
+        let new_byte = self.map_byte(new_byte);
+        let old_byte = std::mem::replace(&mut self.current_bytes[index], new_byte);
+
+        // Only this byte's term changes, and its weight is BASE^(distance to the back)
+        let factor = self.base_powers.get(len - 1 - index);
+        self.current_hash = self.current_hash - factor * old_byte as u64;
+        self.current_hash = self.current_hash + factor * new_byte as u64;
+        if let Some(reversed) = &mut self.reversed_hash {
+            // In the reversed hash, its weight is BASE^(distance to the front)
+            let factor = self.base_powers.get(index);
+            *reversed = *reversed - factor * old_byte as u64;
+            *reversed = *reversed + factor * new_byte as u64;
+        }
+    }
+
+    // Moves the front byte to the back, e.g. "Eiger" -> "igerE"
+    pub fn rotate_left(&mut self) {
+        if let Some(front_byte) = self.current_bytes.pop_front() {
+            let len = self.current_bytes.len() + 1;
+            // Removing it from the front and adding it to the back at once:
+            // hash * BASE - front * BASE^len + front
+            let contribution = self.base_powers.get(len) * front_byte as u64;
+            self.current_hash = self.current_hash * self.base - contribution + front_byte as u64;
+            if let Some(reversed) = &mut self.reversed_hash {
+                // Mirrored: (reversed - front) / BASE + front * BASE^(len-1)
+                let contribution = self.base_powers.get(len - 1) * front_byte as u64;
+                *reversed = (*reversed - front_byte as u64) * self.base_inverse + contribution;
+            }
+            self.current_bytes.push_back(front_byte);
+        }
+    }
+
+    // Moves the back byte to the front, e.g. "Eiger" -> "rEige"
+    pub fn rotate_right(&mut self) {
+        if let Some(back_byte) = self.current_bytes.pop_back() {
+            let len = self.current_bytes.len() + 1;
+            // Removing it from the back and adding it to the front at once:
+            // (hash - back) / BASE + back * BASE^(len-1)
+            let contribution = self.base_powers.get(len - 1) * back_byte as u64;
+            self.current_hash = (self.current_hash - back_byte as u64) * self.base_inverse;
+            self.current_hash = self.current_hash + contribution;
+            if let Some(reversed) = &mut self.reversed_hash {
+                // Mirrored: reversed * BASE - back * BASE^len + back
+                let contribution = self.base_powers.get(len) * back_byte as u64;
+                *reversed = *reversed * self.base - contribution + back_byte as u64;
+            }
+            self.current_bytes.push_front(back_byte);
+        }
+    }
+
     pub fn push_front(&mut self, b: u8) {
+        let b = self.map_byte(b);
+        if self.is_full() {
+            self.pop_back();
+        }
         let len = self.current_bytes.len();
         // We should always have base_powers[len], because we update it on both operations
         // that increase the length: push_back() and push_front()
-        let factor = self.base_powers[len];
+        let factor = self.base_powers.get(len);
         let contribution = factor * (b as u64);
         self.current_hash = self.current_hash + contribution;
+        if let Some(reversed) = &mut self.reversed_hash {
+            *reversed = *reversed * self.base + b as u64;
+        }
         self.current_bytes.push_front(b);
 
         // After we have added a byte, we may need to update our
@@ -117,7 +618,7 @@ impl RollingHash {
 mod tests {
     use std::collections::VecDeque;
 
-    use proptest::proptest;
+    use proptest::prelude::*;
 
     use crate::RollingHash;
 
@@ -131,6 +632,10 @@ mod tests {
         rh.get_current_hash()
     }
 
+    fn hash_from_bytes(bytes: &[u8]) -> u64 {
+        RollingHash::from_initial_bytes(bytes).get_current_hash()
+    }
+
     proptest! {
         #[test]
         fn doesnt_crash(a in 0..255u8, b in 0..255u8, c in 0..255u8) {
@@ -337,6 +842,12 @@ mod tests {
         assert_eq!(rh.get_current_hash(), hash_from_string(""));
     }
 
+    #[test]
+    fn base_inverse_is_correct() {
+        let rh = RollingHash::new();
+        assert_eq!((rh.base_inverse * RollingHash::BASE).value, 1);
+    }
+
     #[test]
     fn push_front_computes_the_correct_hash() {
         let mut rh = RollingHash::from_initial_bytes(b"iger");
@@ -360,6 +871,405 @@ mod tests {
         assert_eq!(rh.get_current_hash(), hash_from_string("Eiger"));
     }
 
+    #[test]
+    fn with_capacity_does_not_reallocate() {
+        let mut rh = RollingHash::with_capacity(100);
+        let capacity = rh.current_bytes.capacity();
+        assert!(capacity >= 100);
+        (0..100u8).for_each(|b| rh.push_back(b));
+        assert_eq!(rh.current_bytes.capacity(), capacity);
+        assert_eq!(
+            rh.get_current_hash(),
+            hash_from_bytes(&(0..100u8).collect::<Vec<u8>>())
+        );
+    }
+
+    #[test]
+    fn set_byte_computes_the_correct_hash() {
+        let mut rh = RollingHash::from_initial_bytes(b"Eiger");
+        rh.set_byte(0, b'T');
+        assert_eq!(rh.get_current_hash(), hash_from_string("Tiger"));
+        rh.set_byte(4, b's');
+        assert_eq!(rh.get_current_hash(), hash_from_string("Tiges"));
+        rh.set_byte(2, b'g');
+        assert_eq!(rh.get_current_bytes(), b"Tiges");
+        assert_eq!(rh.get_current_hash(), hash_from_string("Tiges"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_byte_out_of_bounds_panics() {
+        let mut rh = RollingHash::from_initial_bytes(b"Eiger");
+        rh.set_byte(5, b'!');
+    }
+
+    #[test]
+    fn rotate_left_computes_the_correct_hash() {
+        let mut rh = RollingHash::from_initial_bytes(b"Eiger");
+        rh.rotate_left();
+        assert_eq!(rh.get_current_bytes(), b"igerE");
+        assert_eq!(rh.get_current_hash(), hash_from_string("igerE"));
+        rh.rotate_left();
+        assert_eq!(rh.get_current_hash(), hash_from_string("gerEi"));
+    }
+
+    #[test]
+    fn rotate_right_computes_the_correct_hash() {
+        let mut rh = RollingHash::from_initial_bytes(b"Eiger");
+        rh.rotate_right();
+        assert_eq!(rh.get_current_bytes(), b"rEige");
+        assert_eq!(rh.get_current_hash(), hash_from_string("rEige"));
+        rh.rotate_right();
+        assert_eq!(rh.get_current_hash(), hash_from_string("erEig"));
+    }
+
+    #[test]
+    fn full_rotation_is_identity() {
+        let mut rh = RollingHash::from_initial_bytes(b"Eiger");
+        let initial_hash = rh.get_current_hash();
+        (0..5).for_each(|_| rh.rotate_left());
+        assert_eq!(rh.get_current_hash(), initial_hash);
+        (0..5).for_each(|_| rh.rotate_right());
+        assert_eq!(rh.get_current_hash(), initial_hash);
+
+        let mut empty = RollingHash::new();
+        empty.rotate_left();
+        empty.rotate_right();
+        assert_eq!(empty.get_current_hash(), 0);
+    }
+
+    #[test]
+    fn reversed_hash_is_opt_in() {
+        let mut rh = RollingHash::from_initial_bytes(b"Eiger");
+        assert_eq!(rh.get_reversed_hash(), None);
+        rh.track_reversed_hash();
+        assert_eq!(rh.get_reversed_hash(), Some(hash_from_string("regiE")));
+    }
+
+    #[test]
+    fn reversed_hash_follows_every_operation() {
+        let mut rh = RollingHash::new();
+        rh.track_reversed_hash();
+        let check = |rh: &RollingHash| {
+            let mut reversed = rh.get_current_bytes();
+            reversed.reverse();
+            assert_eq!(rh.get_reversed_hash(), Some(hash_from_bytes(&reversed)));
+        };
+
+        rh.extend_from_slice(b"Eiger");
+        check(&rh);
+        rh.push_front(b'!');
+        check(&rh);
+        rh.pop_front();
+        check(&rh);
+        rh.pop_back();
+        check(&rh);
+        rh.set_byte(1, b'x');
+        check(&rh);
+        rh.rotate_left();
+        check(&rh);
+        rh.rotate_right();
+        rh.rotate_right();
+        check(&rh);
+    }
+
+    #[test]
+    fn reversed_hash_detects_palindromes() {
+        let mut rh = RollingHash::from_initial_bytes(b"racecar");
+        rh.track_reversed_hash();
+        assert_eq!(rh.get_reversed_hash(), Some(rh.get_current_hash()));
+        rh.push_back(b's');
+        assert_ne!(rh.get_reversed_hash(), Some(rh.get_current_hash()));
+    }
+
+    #[test]
+    fn bounded_window_evicts_from_the_front() {
+        let mut rh = RollingHash::with_max_window(3);
+        b"Eiger".iter().for_each(|&b| rh.push_back(b));
+        assert_eq!(rh.get_current_bytes(), b"ger");
+        assert_eq!(rh.get_current_hash(), hash_from_string("ger"));
+    }
+
+    #[test]
+    fn bounded_window_evicts_from_the_back() {
+        let mut rh = RollingHash::with_max_window(3);
+        b"regiE".iter().for_each(|&b| rh.push_front(b));
+        assert_eq!(rh.get_current_bytes(), b"Eig");
+        assert_eq!(rh.get_current_hash(), hash_from_string("Eig"));
+    }
+
+    #[test]
+    fn bounded_window_keeps_memory_bounded() {
+        let mut rh = RollingHash::with_max_window(4);
+        (0..10_000u32).for_each(|i| rh.push_back(i as u8));
+        assert_eq!(rh.current_bytes.len(), 4);
+        assert!(rh.current_bytes.capacity() < 16);
+    }
+
+    #[test]
+    fn long_stream_through_small_window() {
+        let window = 16;
+        let stream: Vec<u8> = (0..200_000u32).map(|i| (i * 31 % 251) as u8).collect();
+        let mut rh = RollingHash::new();
+        for (i, &b) in stream.iter().enumerate() {
+            rh.push_back(b);
+            if i >= window {
+                rh.pop_front();
+            }
+        }
+        let last_window = &stream[stream.len() - window..];
+        let expected = RollingHash::from_initial_bytes(last_window).get_current_hash();
+        assert_eq!(rh.get_current_hash(), expected);
+    }
+
+    #[test]
+    fn equality_is_based_on_content() {
+        let mut rh1 = RollingHash::from_initial_bytes(b"Eiger");
+        let rh2 = RollingHash::from_initial_bytes(b"iger");
+        assert_ne!(rh1, rh2);
+        rh1.pop_front();
+        assert_eq!(rh1, rh2);
+    }
+
+    #[test]
+    fn detect_duplicate_windows_with_a_hash_set() {
+        use std::collections::HashSet;
+
+        let data = b"abcabcabd";
+        let windows: HashSet<RollingHash> = data
+            .windows(3)
+            .map(RollingHash::from_initial_bytes)
+            .collect();
+        // abc, bca, cab, abd
+        assert_eq!(windows.len(), 4);
+        assert!(windows.contains(&RollingHash::from_initial_bytes(b"cab")));
+    }
+
+    #[test]
+    fn fork_shares_the_power_table() {
+        let rh = RollingHash::from_initial_bytes(b"Eige");
+        let mut fork1 = rh.fork();
+        let mut fork2 = rh.fork();
+        assert!(fork1.base_powers.is_shared_with(&rh.base_powers));
+        assert!(fork2.base_powers.is_shared_with(&rh.base_powers));
+
+        fork1.push_back(b'r');
+        fork2.push_back(b'n');
+        assert_eq!(fork1.get_current_hash(), hash_from_string("Eiger"));
+        assert_eq!(fork2.get_current_hash(), hash_from_string("Eigen"));
+        assert_eq!(rh.get_current_hash(), hash_from_string("Eige"));
+    }
+
+    #[test]
+    fn exposes_its_parameters() {
+        let rh = RollingHash::new();
+        assert_eq!(rh.base(), 257);
+        assert_eq!(rh.modulus(), 1_000_000_007);
+        assert_eq!(rh.algorithm_id(), "polynomial");
+    }
+
+    #[test]
+    fn other_bases() {
+        let s1 = "ryIqVm6i3M25uvTttp2Qo8mlkWmKap5PkuWHtS3AZZkRBWCAE9jGCWpkgYHaQobJDJrhdwdoNRGjqQmaTAi5ZGo6hbslnzIL2HaP";
+        let s2 = "eVCblKi7jexBFHudJsTfj8ibzxgXGlol8EthCd8OBniEXI6tVR9LFkNzPtNeqR3EIVERZwtG1uxFimT3cPQAHwTTiuRnj6gHh406";
+        let mut rh1 = RollingHash::from_initial_bytes(s1.as_bytes());
+        let mut rh2 = RollingHash::from_initial_bytes(s2.as_bytes());
+        // The collision found for BASE does not survive a change of base
+        rh1.set_base(131);
+        rh2.set_base(131);
+        assert_ne!(rh1.get_current_hash(), rh2.get_current_hash());
+        assert_eq!(rh1.get_current_hash(), rh1.params().hash(s1.as_bytes()));
+
+        let mut rh = RollingHash::new();
+        rh.set_base(131);
+        rh.track_reversed_hash();
+        rh.extend_from_slice(b"Eiger");
+        rh.pop_front();
+        rh.push_front(b'T');
+        rh.rotate_left();
+        rh.pop_back();
+        let expected = RollingHash::from_initial_bytes(b"igerT");
+        assert_eq!(rh.get_current_bytes(), b"iger");
+        assert_eq!(rh.get_current_hash(), rh.params().hash(b"iger"));
+        assert_eq!(rh.get_reversed_hash(), Some(rh.params().hash(b"regi")));
+        assert_ne!(rh, expected);
+
+        rh.set_base(257);
+        assert_eq!(rh.params(), expected.params());
+        assert_eq!(rh.get_current_hash(), hash_from_string("iger"));
+    }
+
+    #[test]
+    fn random_bases_are_fixed_per_process() {
+        let mut rh1 = RollingHash::from_initial_bytes(b"Eiger");
+        let mut rh2 = RollingHash::new();
+        rh1.randomize_base();
+        rh2.randomize_base();
+        rh2.extend_from_slice(b"Eiger");
+        assert_eq!(rh1.base(), rh2.base());
+        assert_eq!(rh1, rh2);
+        assert_eq!(rh1.params().name, format!("1e9+7/{}", rh1.base()));
+    }
+
+    #[test]
+    fn truncate_back_and_keep_last() {
+        let mut rh = RollingHash::from_initial_bytes(b"Eiger, Moench and Jungfrau");
+        rh.truncate_back(13);
+        assert_eq!(rh.get_current_bytes(), b"Eiger, Moench");
+        assert_eq!(rh.get_current_hash(), hash_from_string("Eiger, Moench"));
+        rh.keep_last(6);
+        assert_eq!(rh.get_current_bytes(), b"Moench");
+        assert_eq!(rh.get_current_hash(), hash_from_string("Moench"));
+        rh.truncate_back(10);
+        rh.keep_last(10);
+        assert_eq!(rh.get_current_hash(), hash_from_string("Moench"));
+        rh.keep_last(0);
+        assert!(rh.is_empty());
+        assert_eq!(rh.get_current_hash(), 0);
+    }
+
+    #[test]
+    fn drain_front_returns_the_bytes() {
+        let mut rh = RollingHash::from_initial_bytes(b"Eiger, Moench");
+        assert_eq!(rh.drain_front(7), b"Eiger, ");
+        assert_eq!(rh.get_current_hash(), hash_from_string("Moench"));
+        assert_eq!(rh.drain_front(100), b"Moench");
+        assert!(rh.is_empty());
+        assert_eq!(rh.drain_front(1), b"");
+    }
+
+    #[test]
+    fn split_off_hashes_both_halves() {
+        let mut rh = RollingHash::from_initial_bytes(b"EigerMoench");
+        rh.track_reversed_hash();
+        let tail = rh.split_off(5);
+        assert_eq!(rh.get_current_bytes(), b"Eiger");
+        assert_eq!(tail.get_current_bytes(), b"Moench");
+        assert_eq!(rh.get_current_hash(), hash_from_string("Eiger"));
+        assert_eq!(tail.get_current_hash(), hash_from_string("Moench"));
+        assert_eq!(rh.get_reversed_hash(), Some(hash_from_string("regiE")));
+        assert_eq!(tail.get_reversed_hash(), Some(hash_from_string("hcneoM")));
+
+        let empty = rh.split_off(5);
+        assert!(empty.is_empty());
+        assert_eq!(
+            rh.split_off(0).get_current_hash(),
+            hash_from_string("Eiger")
+        );
+        assert_eq!(rh.get_current_hash(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_off_past_the_end() {
+        RollingHash::from_initial_bytes(b"Eiger").split_off(6);
+    }
+
+    #[test]
+    fn borrowing_the_bytes() {
+        let mut rh = RollingHash::from_initial_bytes(b"iger");
+        rh.push_front(b'E');
+        let (first, second) = rh.as_slices();
+        assert_eq!([first, second].concat(), b"Eiger");
+        assert!(rh.iter().eq(b"Eiger"));
+        assert_eq!(rh.iter().len(), 5);
+    }
+
+    #[test]
+    fn indexing_the_window() {
+        let mut rh = RollingHash::with_max_window(5);
+        rh.extend_from_slice(b"Eiger!");
+        assert_eq!(rh[0], b'i');
+        assert_eq!(rh[4], b'!');
+        assert_eq!(rh.get(4), Some(b'!'));
+        assert_eq!(rh.get(5), None);
+        let mut bytes = Vec::new();
+        for &b in &rh {
+            bytes.push(b);
+        }
+        assert_eq!(bytes, b"iger!");
+    }
+
+    #[test]
+    fn append_combines_the_hashes() {
+        let mut rh = RollingHash::from_initial_bytes(b"Eiger");
+        let mut other = RollingHash::from_initial_bytes(b"Moench");
+        rh.append(&mut other);
+        assert_eq!(rh.get_current_bytes(), b"EigerMoench");
+        assert_eq!(rh.get_current_hash(), hash_from_string("EigerMoench"));
+        assert!(other.is_empty());
+        assert_eq!(other.get_current_hash(), 0);
+
+        // A full window evicts along the way
+        let mut bounded = RollingHash::with_max_window(4);
+        bounded.extend_from_slice(b"Jung");
+        bounded.append(&mut RollingHash::from_initial_bytes(b"frau"));
+        assert_eq!(bounded.get_current_bytes(), b"frau");
+        assert_eq!(bounded.get_current_hash(), hash_from_string("frau"));
+    }
+
+    proptest! {
+        #[test]
+        fn batch_shrinking_matches_popping(
+            bytes in prop::collection::vec(any::<u8>(), 0..100),
+            // Pushed at the front, so the deque wraps around
+            front in prop::collection::vec(any::<u8>(), 0..20),
+            len in 0..130usize,
+            reversed in any::<bool>(),
+            other_base in any::<bool>(),
+        ) {
+            let mut expected = RollingHash::from_initial_bytes(&bytes);
+            front.iter().for_each(|&b| expected.push_front(b));
+            if reversed {
+                expected.track_reversed_hash();
+            }
+            if other_base {
+                expected.set_base(131);
+            }
+            let mut truncated = expected.clone();
+            let mut kept = expected.clone();
+            let mut popped_back = expected.clone();
+            let mut popped_front = expected;
+
+            truncated.truncate_back(len);
+            kept.keep_last(len);
+            while popped_back.len() > len {
+                popped_back.pop_back();
+                popped_front.pop_front();
+            }
+            prop_assert_eq!(&truncated, &popped_back);
+            prop_assert_eq!(&kept, &popped_front);
+            prop_assert_eq!(truncated.get_reversed_hash(), popped_back.get_reversed_hash());
+            prop_assert_eq!(kept.get_reversed_hash(), popped_front.get_reversed_hash());
+        }
+
+        #[test]
+        fn append_matches_pushing(
+            head in prop::collection::vec(any::<u8>(), 0..50),
+            tail in prop::collection::vec(any::<u8>(), 0..50),
+            reversed in any::<bool>(),
+            other_base in any::<bool>(),
+        ) {
+            let mut rh = RollingHash::from_initial_bytes(&head);
+            let mut expected = rh.clone();
+            let mut other = RollingHash::from_initial_bytes(&tail);
+            if reversed {
+                rh.track_reversed_hash();
+                expected.track_reversed_hash();
+            }
+            if other_base {
+                rh.set_base(131);
+                expected.set_base(131);
+                other.set_base(131);
+            }
+            rh.append(&mut other);
+            tail.iter().for_each(|&b| expected.push_back(b));
+            prop_assert_eq!(&rh, &expected);
+            prop_assert_eq!(rh.get_reversed_hash(), expected.get_reversed_hash());
+            prop_assert!(other.is_empty());
+        }
+    }
+
     #[test]
     fn big_string_also_works() {
         // The powers here will surely be bigger than MODULO, so if this works MODULO is ok
@@ -0,0 +1,208 @@
+use std::ops::Range;
+
+use crate::powers::PowerTable;
+use crate::{Numeric, RollingHash};
+
+// 2D polynomial hash: the hash of an r x c block a is
+// sum(a[i][j] * ROW_BASE^(r-1-i) * BASE^(c-1-j)), so a single row hashes exactly
+// like RollingHash. We keep the hash of every top-left block, and any block is
+// then four lookups away, like a 2D prefix sum
+pub struct Matrix2DHasher {
+    rows: usize,
+    cols: usize,
+    // (rows + 1) x (cols + 1), prefix[r][c] is the hash of the block [0, r) x [0, c)
+    prefix: Vec<Numeric>,
+    row_powers: Vec<Numeric>,
+    col_powers: PowerTable,
+}
+
+impl Matrix2DHasher {
+    // Needs to differ from BASE, or transposed blocks would collide
+    const ROW_BASE: u64 = 131_071;
+
+    // Panics if the rows have different lengths
+    pub fn new(matrix: &[&[u8]]) -> Self {
+        let rows = matrix.len();
+        let cols = matrix.first().map_or(0, |row| row.len());
+        assert!(
+            matrix.iter().all(|row| row.len() == cols),
+            "all rows must have the same length"
+        );
+
+        let mut col_powers = PowerTable::new();
+        col_powers.ensure(cols);
+        let mut row_powers = vec![Numeric::from_u64(1)];
+        for _ in 0..rows {
+            let &last = row_powers.last().unwrap();
+            row_powers.push(last * Self::ROW_BASE);
+        }
+
+        let width = cols + 1;
+        let mut prefix = vec![Numeric::from_u64(0); (rows + 1) * width];
+        for (i, row) in matrix.iter().enumerate() {
+            // Hash of row i's first c bytes, appended below the block above it
+            let mut row_hash = Numeric::from_u64(0);
+            for (j, &byte) in row.iter().enumerate() {
+                row_hash = row_hash * RollingHash::BASE + byte as u64;
+                prefix[(i + 1) * width + j + 1] =
+                    prefix[i * width + j + 1] * Self::ROW_BASE + row_hash;
+            }
+        }
+
+        Self {
+            rows,
+            cols,
+            prefix,
+            row_powers,
+            col_powers,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    // The hash of the block rows x cols. Panics if it is out of bounds
+    pub fn hash(&self, rows: Range<usize>, cols: Range<usize>) -> u64 {
+        assert!(
+            rows.start <= rows.end && rows.end <= self.rows,
+            "rows out of bounds"
+        );
+        assert!(
+            cols.start <= cols.end && cols.end <= self.cols,
+            "cols out of bounds"
+        );
+
+        let row_shift = self.row_powers[rows.len()];
+        let col_shift = self.col_powers.get(cols.len());
+        let hash = self.prefix(rows.end, cols.end)
+            - self.prefix(rows.start, cols.end) * row_shift
+            - self.prefix(rows.end, cols.start) * col_shift
+            + self.prefix(rows.start, cols.start) * row_shift * col_shift;
+        hash.value()
+    }
+
+    // Hashes of every height x width block, row by row
+    pub fn windows(&self, height: usize, width: usize) -> SubmatrixHashes<'_> {
+        assert!(height > 0 && width > 0, "window must be non-empty");
+        SubmatrixHashes {
+            hasher: self,
+            height,
+            width,
+            row: 0,
+            col: 0,
+        }
+    }
+
+    fn prefix(&self, row: usize, col: usize) -> Numeric {
+        self.prefix[row * (self.cols + 1) + col]
+    }
+}
+
+#[derive(Clone)]
+pub struct SubmatrixHashes<'a> {
+    hasher: &'a Matrix2DHasher,
+    height: usize,
+    width: usize,
+    // Top-left corner of the next block
+    row: usize,
+    col: usize,
+}
+
+impl Iterator for SubmatrixHashes<'_> {
+    // (top row, left column, hash)
+    type Item = (usize, usize, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.width > self.hasher.cols {
+            return None;
+        }
+        if self.col + self.width > self.hasher.cols {
+            self.row += 1;
+            self.col = 0;
+        }
+        if self.row + self.height > self.hasher.rows {
+            return None;
+        }
+
+        let (row, col) = (self.row, self.col);
+        self.col += 1;
+        let hash = self
+            .hasher
+            .hash(row..row + self.height, col..col + self.width);
+        Some((row, col, hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use proptest::prelude::*;
+
+    use crate::matrix::Matrix2DHasher;
+    use crate::RollingHash;
+
+    fn hash_block(matrix: &[&[u8]], rows: Range<usize>, cols: Range<usize>) -> u64 {
+        let block: Vec<&[u8]> = matrix[rows].iter().map(|row| &row[cols.clone()]).collect();
+        Matrix2DHasher::new(&block).hash(0..block.len(), 0..cols.len())
+    }
+
+    #[test]
+    fn a_row_hashes_like_rolling_hash() {
+        let hasher = Matrix2DHasher::new(&[&b"Eiger"[..], b"Tiger"]);
+        let expected = RollingHash::from_initial_bytes(b"iger").get_current_hash();
+        assert_eq!(hasher.hash(0..1, 1..5), expected);
+        assert_eq!(hasher.hash(1..2, 1..5), expected);
+        assert_eq!(hasher.hash(0..0, 0..5), 0);
+    }
+
+    #[test]
+    fn equal_blocks_share_a_hash() {
+        let matrix: [&[u8]; 3] = [b"abab", b"cdcd", b"abab"];
+        let hasher = Matrix2DHasher::new(&matrix);
+        assert_eq!(hasher.hash(0..2, 0..2), hasher.hash(0..2, 2..4));
+        assert_ne!(hasher.hash(0..2, 0..2), hasher.hash(1..3, 0..2));
+    }
+
+    #[test]
+    fn transposed_blocks_differ() {
+        let hasher = Matrix2DHasher::new(&[&b"ab"[..], b"cd"]);
+        let transposed = Matrix2DHasher::new(&[&b"ac"[..], b"bd"]);
+        assert_ne!(hasher.hash(0..2, 0..2), transposed.hash(0..2, 0..2));
+    }
+
+    #[test]
+    fn sliding_windows() {
+        let matrix: [&[u8]; 3] = [b"abc", b"def", b"ghi"];
+        let hasher = Matrix2DHasher::new(&matrix);
+        let windows: Vec<_> = hasher.windows(2, 2).collect();
+        let corners: Vec<_> = windows.iter().map(|&(row, col, _)| (row, col)).collect();
+        assert_eq!(corners, vec![(0, 0), (0, 1), (1, 0), (1, 1)]);
+        assert_eq!(windows[3].2, hash_block(&matrix, 1..3, 1..3));
+        assert_eq!(hasher.windows(4, 1).count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ragged_rows() {
+        Matrix2DHasher::new(&[&b"Eiger"[..], b"Tig"]);
+    }
+
+    proptest! {
+        #[test]
+        fn blocks_match_hashing_them_alone(
+            cells in prop::collection::vec(any::<u8>(), 12),
+            r1 in 0..3usize, r2 in 0..3usize, c1 in 0..4usize, c2 in 0..4usize,
+        ) {
+            let matrix: Vec<&[u8]> = cells.chunks(4).collect();
+            let (rows, cols) = (r1.min(r2)..r1.max(r2) + 1, c1.min(c2)..c1.max(c2) + 1);
+            let hasher = Matrix2DHasher::new(&matrix);
+            prop_assert_eq!(hasher.hash(rows.clone(), cols.clone()), hash_block(&matrix, rows, cols));
+        }
+    }
+}
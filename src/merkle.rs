@@ -0,0 +1,218 @@
+// Every node is hashed with a tag saying what it is, so that a leaf can never
+// pass for an inner node or the other way round. Without them, [a, b, c] and
+// [parent(a, b), c] would share a root
+const LEAF: u8 = 0;
+const INNER: u8 = 1;
+// The last node of an odd-sized level, hashed alone rather than promoted
+const LONE: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sibling {
+    Left(u64),
+    Right(u64),
+    // The node was last on an odd-sized level and had no sibling
+    Lone,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub index: usize,
+    // From the leaf level up to (but not including) the root
+    pub siblings: Vec<Sibling>,
+}
+
+impl InclusionProof {
+    // Also checks that the path of siblings leads to `index`, so a proof only
+    // verifies at the position it was made for
+    pub fn verify(&self, leaf: u64, root: u64) -> bool {
+        let mut current = hash_node(LEAF, &[leaf]);
+        let mut position = self.index;
+        for sibling in &self.siblings {
+            let is_right_child = position % 2 == 1;
+            current = match *sibling {
+                Sibling::Left(left) if is_right_child => hash_node(INNER, &[left, current]),
+                Sibling::Right(right) if !is_right_child => hash_node(INNER, &[current, right]),
+                Sibling::Lone if !is_right_child => hash_node(LONE, &[current]),
+                _ => return false,
+            };
+            position /= 2;
+        }
+        position == 0 && current == root
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    leaves: Vec<u64>,
+    // levels[0] are the hashed leaves, levels.last() holds only the root
+    levels: Vec<Vec<u64>>,
+}
+
+// Node hashes are polynomial hashes modulo the Mersenne prime 2^61 - 1: far
+// wider than the 30-bit rolling hash, but still not a cryptographic hash, so
+// roots are no defence against someone crafting collisions on purpose
+const MODULUS: u128 = (1 << 61) - 1;
+const BASE: u128 = 0x1_a2f6_e9b3_c4d5;
+
+// The tag followed by the children's little-endian bytes
+fn hash_node(tag: u8, children: &[u64]) -> u64 {
+    let bytes = children.iter().flat_map(|child| child.to_le_bytes());
+    let hash = std::iter::once(tag)
+        .chain(bytes)
+        .fold(0, |hash, byte| (hash * BASE + byte as u128) % MODULUS);
+    hash as u64
+}
+
+impl MerkleTree {
+    pub fn new(leaves: &[u64]) -> Self {
+        let mut levels = vec![leaves
+            .iter()
+            .map(|&leaf| hash_node(LEAF, &[leaf]))
+            .collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            let current = levels.last().unwrap();
+            // A lone node at the end gets its own tag, instead of being paired
+            // with a copy of itself, which would give [a, b, c] and [a, b, c, c]
+            // the same root
+            let next = current
+                .chunks(2)
+                .map(|pair| match *pair {
+                    [left, right] => hash_node(INNER, &[left, right]),
+                    [single] => hash_node(LONE, &[single]),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self {
+            leaves: leaves.to_vec(),
+            levels,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn leaves(&self) -> &[u64] {
+        &self.leaves
+    }
+
+    // An empty tree has no root
+    pub fn root(&self) -> Option<u64> {
+        self.levels.last().unwrap().first().copied()
+    }
+
+    pub fn proof(&self, index: usize) -> Option<InclusionProof> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let mut siblings = Vec::new();
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling_position = position ^ 1;
+            match level.get(sibling_position) {
+                Some(&sibling) if sibling_position < position => {
+                    siblings.push(Sibling::Left(sibling))
+                }
+                Some(&sibling) => siblings.push(Sibling::Right(sibling)),
+                None => siblings.push(Sibling::Lone),
+            }
+            position /= 2;
+        }
+
+        Some(InclusionProof { index, siblings })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::merkle::{hash_node, MerkleTree, INNER, LEAF};
+
+    fn leaves(n: u64) -> Vec<u64> {
+        (0..n).map(|i| i * 7919 + 13).collect()
+    }
+
+    #[test]
+    fn empty_tree_has_no_root() {
+        let tree = MerkleTree::new(&[]);
+        assert!(tree.is_empty());
+        assert_eq!(tree.root(), None);
+        assert_eq!(tree.proof(0), None);
+    }
+
+    #[test]
+    fn single_leaf_is_the_root() {
+        let tree = MerkleTree::new(&[42]);
+        let root = hash_node(LEAF, &[42]);
+        assert_eq!(tree.root(), Some(root));
+        let proof = tree.proof(0).unwrap();
+        assert!(proof.siblings.is_empty());
+        assert!(proof.verify(42, root));
+    }
+
+    #[test]
+    fn every_leaf_has_a_valid_proof() {
+        for n in 1..=17 {
+            let leaves = leaves(n);
+            let tree = MerkleTree::new(&leaves);
+            let root = tree.root().unwrap();
+            for (index, &leaf) in leaves.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert!(proof.verify(leaf, root), "n = {n}, index = {index}");
+            }
+        }
+    }
+
+    #[test]
+    fn tampered_leaf_fails_verification() {
+        let leaves = leaves(10);
+        let tree = MerkleTree::new(&leaves);
+        let root = tree.root().unwrap();
+        let proof = tree.proof(3).unwrap();
+        assert!(!proof.verify(leaves[3] + 1, root));
+        assert!(!proof.verify(leaves[4], root));
+    }
+
+    #[test]
+    fn root_depends_on_leaf_order() {
+        let tree1 = MerkleTree::new(&[1, 2, 3]);
+        let tree2 = MerkleTree::new(&[2, 1, 3]);
+        assert_ne!(tree1.root(), tree2.root());
+    }
+
+    #[test]
+    fn proofs_only_verify_at_their_index() {
+        let leaves = leaves(10);
+        let tree = MerkleTree::new(&leaves);
+        let root = tree.root().unwrap();
+        for (index, &leaf) in leaves.iter().enumerate() {
+            let mut proof = tree.proof(index).unwrap();
+            for wrong in [index ^ 1, index + 16, index + 1024] {
+                proof.index = wrong;
+                assert!(!proof.verify(leaf, root), "{index} as {wrong}");
+            }
+        }
+    }
+
+    #[test]
+    fn inner_nodes_do_not_pass_for_leaves() {
+        let [a, b, c] = [1, 2, 3];
+        let root = MerkleTree::new(&[a, b, c]).root();
+        let parent = hash_node(INNER, &[hash_node(LEAF, &[a]), hash_node(LEAF, &[b])]);
+        assert_ne!(MerkleTree::new(&[parent, c]).root(), root);
+    }
+
+    #[test]
+    fn odd_leaf_is_not_duplicated() {
+        // If the lone node were paired with itself, these would collide
+        let tree1 = MerkleTree::new(&[1, 2, 3]);
+        let tree2 = MerkleTree::new(&[1, 2, 3, 3]);
+        assert_ne!(tree1.root(), tree2.root());
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::VecDeque;
+
+use crate::nthash::{canonical_kmer_hashes, KmerHashes};
+
+// (w, k)-minimizers of a DNA sequence: in every window of w consecutive k-mers,
+// the one with the smallest canonical ntHash (the leftmost one on ties). A
+// k-mer selected by several windows in a row is yielded once. Windows never
+// span a k-mer that was skipped (e.g. around an N), so sampling restarts there
+pub fn minimizers(seq: &[u8], k: usize, w: usize) -> Minimizers<KmerHashes<'_>> {
+    Minimizers::new(canonical_kmer_hashes(seq, k), w)
+}
+
+#[derive(Debug, Clone)]
+pub struct Minimizers<I> {
+    hashes: I,
+    w: usize,
+    // (position, hash) with non-decreasing hashes, so the front is the
+    // minimum of the current window
+    candidates: VecDeque<(usize, u64)>,
+    previous_position: Option<usize>,
+    // How many consecutive k-mers end at previous_position
+    run: usize,
+    last_selected: Option<usize>,
+}
+
+impl<I: Iterator<Item = (usize, u64)>> Minimizers<I> {
+    // Works over any stream of (position, hash) pairs, e.g. from kmer_hashes()
+    // for forward-strand minimizers
+    pub fn new(hashes: I, w: usize) -> Self {
+        assert!(w > 0, "w must be positive");
+        Self {
+            hashes,
+            w,
+            candidates: VecDeque::new(),
+            previous_position: None,
+            run: 0,
+            last_selected: None,
+        }
+    }
+}
+
+impl<I: Iterator<Item = (usize, u64)>> Iterator for Minimizers<I> {
+    type Item = (usize, u64);
+
+    fn next(&mut self) -> Option<(usize, u64)> {
+        loop {
+            let (position, hash) = self.hashes.next()?;
+            if self.previous_position.map(|previous| previous + 1) != Some(position) {
+                self.candidates.clear();
+                self.run = 0;
+            }
+            self.previous_position = Some(position);
+            self.run += 1;
+
+            // Using > keeps the leftmost of equal hashes
+            while matches!(self.candidates.back(), Some(&(_, back)) if back > hash) {
+                self.candidates.pop_back();
+            }
+            self.candidates.push_back((position, hash));
+            if self.candidates[0].0 + self.w <= position {
+                self.candidates.pop_front();
+            }
+
+            if self.run >= self.w {
+                let selected = self.candidates[0];
+                if self.last_selected != Some(selected.0) {
+                    self.last_selected = Some(selected.0);
+                    return Some(selected);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::minimizers::{minimizers, Minimizers};
+    use crate::nthash::canonical_kmer_hash;
+
+    #[test]
+    fn leftmost_minimum_of_every_window() {
+        let hashes = [(0, 5), (1, 3), (2, 3), (3, 7), (4, 1), (5, 9)];
+        let selected: Vec<_> = Minimizers::new(hashes.into_iter(), 3).collect();
+        assert_eq!(selected, vec![(1, 3), (4, 1)]);
+    }
+
+    #[test]
+    fn windows_do_not_span_skipped_k_mers() {
+        // The k-mers at 1..=3 contain the N
+        let hashes = [(0, 1), (4, 9), (5, 8)];
+        let selected: Vec<_> = Minimizers::new(hashes.into_iter(), 2).collect();
+        assert_eq!(selected, vec![(5, 8)]);
+    }
+
+    proptest! {
+        #[test]
+        fn every_window_has_its_minimizer(
+            seq in prop::collection::vec(prop::sample::select(b"ACGT".to_vec()), 0..300),
+            k in 1..12usize,
+            w in 1..10usize,
+        ) {
+            let selected: Vec<(usize, u64)> = minimizers(&seq, k, w).collect();
+            let hashes: Vec<u64> = seq
+                .windows(k)
+                .map(|kmer| canonical_kmer_hash(kmer).unwrap())
+                .collect();
+            for (start, window) in hashes.windows(w).enumerate() {
+                let min = *window.iter().min().unwrap();
+                let leftmost = start + window.iter().position(|&hash| hash == min).unwrap();
+                prop_assert!(selected.contains(&(leftmost, min)));
+            }
+            prop_assert!(selected.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        }
+    }
+}
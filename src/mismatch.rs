@@ -0,0 +1,57 @@
+use std::ops::Range;
+
+// The first index where two strings differ, or the length of the shorter one if
+// it is a prefix of the other; None if they are equal. Binary search on prefix
+// hashes, so O(log n) calls to `range_hash_a`/`range_hash_b` instead of a byte
+// scan. The result is probabilistic: a collision on some prefix makes it report
+// a later index than the true one, and equal hashes on the whole common part
+// make unequal strings of the same length come out as None
+pub(crate) fn first_mismatch(
+    len_a: usize,
+    len_b: usize,
+    range_hash_a: impl Fn(Range<usize>) -> u64,
+    range_hash_b: impl Fn(Range<usize>) -> u64,
+) -> Option<usize> {
+    let common = len_a.min(len_b);
+    // The longest matching prefix is between low and high
+    let (mut low, mut high) = (0, common);
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if range_hash_a(0..mid) == range_hash_b(0..mid) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    if low == common && len_a == len_b {
+        return None;
+    }
+    Some(low)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mismatch::first_mismatch;
+    use crate::RollingHash;
+
+    #[test]
+    fn agrees_with_a_byte_scan() {
+        let hash = |bytes: &'static [u8]| {
+            move |range: std::ops::Range<usize>| {
+                RollingHash::from_initial_bytes(&bytes[range]).get_current_hash()
+            }
+        };
+        let (a, b) = (b"Jungfrau", b"Jungle");
+        assert_eq!(first_mismatch(8, 6, hash(a), hash(b)), Some(4));
+        assert_eq!(first_mismatch(8, 8, hash(a), hash(a)), None);
+        assert_eq!(first_mismatch(8, 0, hash(a), hash(b"")), Some(0));
+    }
+
+    #[test]
+    fn collisions_shift_the_result() {
+        // A hash that only sees lengths makes every prefix match
+        let by_len = |range: std::ops::Range<usize>| range.len() as u64;
+        assert_eq!(first_mismatch(5, 5, by_len, by_len), None);
+        assert_eq!(first_mismatch(5, 3, by_len, by_len), Some(3));
+    }
+}
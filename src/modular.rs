@@ -28,7 +28,7 @@ impl<const MOD: u64> std::ops::Mul for Modular<MOD> {
 
     fn mul(self, rhs: Self) -> Self::Output {
         Self::Output {
-            value: (self.value * rhs.value) % MOD,
+            value: Self::mul_mod(self.value, rhs.value),
         }
     }
 }
@@ -38,7 +38,7 @@ impl<const MOD: u64> std::ops::Mul<u64> for Modular<MOD> {
 
     fn mul(self, rhs: u64) -> Self::Output {
         Self::Output {
-            value: (self.value * rhs) % MOD,
+            value: Self::mul_mod(self.value, rhs),
         }
     }
 }
@@ -97,6 +97,12 @@ impl<const MOD: u64> std::ops::Div<u64> for Modular<MOD> {
     }
 }
 
+impl<const MOD: u64> From<Modular<MOD>> for u64 {
+    fn from(number: Modular<MOD>) -> Self {
+        number.value
+    }
+}
+
 impl<const MOD: u64> Modular<MOD> {
     pub fn from_u64(number: u64) -> Self {
         Self {
@@ -104,12 +110,64 @@ impl<const MOD: u64> Modular<MOD> {
         }
     }
 
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn into_inner(self) -> u64 {
+        self.value
+    }
+
+    pub fn pow(self, exponent: u64) -> Self {
+        Self {
+            value: Self::fast_exponentiation(self.value, exponent),
+        }
+    }
+
+    // The inverse only exists when the number and MOD are coprime, so it never exists
+    // for 0, and for a composite MOD it is also missing for every multiple of its factors
+    // Reference: https://cp-algorithms.com/algebra/module-inverse.html#finding-the-modular-inverse-using-extended-euclidean-algorithm
+    pub fn try_inverse(self) -> Option<Self> {
+        // Invariant: old_r = old_s * value (mod MOD), and the same for r and s
+        let (mut old_r, mut r) = (self.value as i128, MOD as i128);
+        let (mut old_s, mut s) = (1i128, 0i128);
+        while r != 0 {
+            let quotient = old_r / r;
+            (old_r, r) = (r, old_r - quotient * r);
+            (old_s, s) = (s, old_s - quotient * s);
+        }
+
+        // old_r is now gcd(value, MOD)
+        if old_r != 1 {
+            return None;
+        }
+        Some(Self {
+            value: old_s.rem_euclid(MOD as i128) as u64,
+        })
+    }
+
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        Some(self * rhs.try_inverse()?)
+    }
+
     // Division is tricky under modulo, we need to actually multiply by the modular multiplicative inverse
     // See: https://cp-algorithms.com/algebra/module-inverse.html
+    // The `Div` impls rely on this, and panic when there is no inverse. Use checked_div() otherwise
     fn find_modular_inverse(number: u64) -> u64 {
-        // TODO: I have never really understood this
-        // Reference: https://cp-algorithms.com/algebra/module-inverse.html#finding-the-modular-inverse-using-binary-exponentiation
-        Self::fast_exponentiation(number, MOD - 2)
+        match Self::from_u64(number).try_inverse() {
+            Some(inverse) => inverse.value,
+            None => panic!("{number} has no inverse modulo {MOD}"),
+        }
+    }
+
+    // a * b % MOD without overflow. Factors below 2^32 (always the case for
+    // moduli up to 2^32) multiply in u64; anything larger goes through u128,
+    // which is much slower
+    fn mul_mod(a: u64, b: u64) -> u64 {
+        if a <= u32::MAX as u64 && b <= u32::MAX as u64 {
+            return a * b % MOD;
+        }
+        (a as u128 * b as u128 % MOD as u128) as u64
     }
 
     // Uses Modulo
@@ -119,11 +177,9 @@ impl<const MOD: u64> Modular<MOD> {
         let mut result = 1;
         while exponent != 0 {
             if is_last_bit_on(exponent) {
-                result *= base;
-                result %= MOD;
+                result = Self::mul_mod(result, base);
             }
-            base *= base;
-            base %= MOD;
+            base = Self::mul_mod(base, base);
             exponent >>= 1; // Shift the bits
         }
 
@@ -163,6 +219,18 @@ mod tests {
         assert_eq!((lhs * rhs).value, 5);
     }
 
+    #[test]
+    fn multiply_beyond_32_bits() {
+        const MERSENNE_61: u64 = (1 << 61) - 1;
+        let lhs = Modular::<MERSENNE_61>::from_u64(MERSENNE_61 - 1);
+        // (-1) * (-1) = 1
+        assert_eq!((lhs * lhs).value, 1);
+        assert_eq!((lhs * (MERSENNE_61 - 1)).value, 1);
+        assert_eq!(lhs.pow(3).value, MERSENNE_61 - 1);
+        // A small modulus with a factor that does not fit in 32 bits
+        assert_eq!((Modular::<25> { value: 5 } * u64::MAX).value, 0);
+    }
+
     #[test]
     fn subtract_modular() {
         let lhs = Modular::<25> { value: 10 };
@@ -177,6 +245,16 @@ mod tests {
         assert_eq!((lhs - rhs).value, 20);
     }
 
+    #[test]
+    fn extract_value() {
+        let number = Modular::<25>::from_u64(27);
+        assert_eq!(number.value(), 2);
+        assert_eq!(number.into_inner(), 2);
+        assert_eq!(u64::from(number), 2);
+        let as_u64: u64 = number.into();
+        assert_eq!(as_u64, 2);
+    }
+
     #[test]
     fn fast_exponentiation_works() {
         const BIG_PRIME: u64 = 1_000_000_007;
@@ -189,6 +267,13 @@ mod tests {
         assert_eq!(M::fast_exponentiation(257, 4), 362470373);
     }
 
+    #[test]
+    fn pow_modular() {
+        let base = Modular::<25> { value: 3 };
+        assert_eq!(base.pow(0).value, 1);
+        assert_eq!(base.pow(3).value, 2);
+    }
+
     #[test]
     fn modular_multiplicative_inverse_works() {
         const BIG_PRIME: u64 = 1_000_000_007;
@@ -196,6 +281,40 @@ mod tests {
         assert_eq!((200 * 285000002) % BIG_PRIME, 1);
     }
 
+    #[test]
+    fn try_inverse_works() {
+        let number = Modular::<23> { value: 5 };
+        let inverse = number.try_inverse().unwrap();
+        assert_eq!((number * inverse).value, 1);
+
+        // Composite modulus, coprime number
+        let number = Modular::<25> { value: 7 };
+        let inverse = number.try_inverse().unwrap();
+        assert_eq!((number * inverse).value, 1);
+    }
+
+    #[test]
+    fn try_inverse_fails_without_inverse() {
+        assert_eq!(Modular::<23> { value: 0 }.try_inverse(), None);
+        assert_eq!(Modular::<25> { value: 10 }.try_inverse(), None);
+    }
+
+    #[test]
+    fn checked_div_works() {
+        let lhs = Modular::<25> { value: 8 };
+        let div = lhs.checked_div(Modular { value: 7 }).unwrap();
+        assert_eq!(div * 7, lhs);
+        assert_eq!(lhs.checked_div(Modular { value: 0 }), None);
+        assert_eq!(lhs.checked_div(Modular { value: 15 }), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn divide_by_zero_panics() {
+        let lhs = Modular::<23> { value: 8 };
+        let _ = lhs / 0;
+    }
+
     #[test]
     fn divide_modular() {
         let lhs = Modular::<23> { value: 8 };
@@ -0,0 +1,306 @@
+// ntHash, from "ntHash: recursive nucleotide hashing" (Mohamadi et al.), for
+// k-mers of DNA. Every base has a fixed random 64-bit seed, and the hash of
+// s_0 .. s_{k-1} is rol^(k-1)(seed(s_0)) ^ ... ^ rol^0(seed(s_{k-1})), so rolling
+// is a rotation and two XORs: no multiplication, no modulus, no power table.
+// Bases are A, C, G and T in either case. Anything else (e.g. N) cannot be
+// part of a k-mer, and the k-mers around it are skipped.
+// The canonical variants give a k-mer and its reverse complement (the same
+// sequence read off the other strand) the same hash, min(forward, reverse).
+// The _packed variants read 2-bit packed sequences (see PackedSeq) directly
+
+// Seeds from the paper, indexed by code()
+const SEEDS: [u64; 4] = [
+    0x3c8b_fbb3_95c6_0474,
+    0x3193_c185_62a0_2b4c,
+    0x2032_3ed0_8257_2324,
+    0x2955_49f5_4be2_4456,
+];
+
+// A = 0, C = 1, G = 2, T = 3, so the complement of a base is 3 - code
+fn code(base: u8) -> Option<u8> {
+    match base {
+        b'A' | b'a' => Some(0),
+        b'C' | b'c' => Some(1),
+        b'G' | b'g' => Some(2),
+        b'T' | b't' => Some(3),
+        _ => None,
+    }
+}
+
+// None if `kmer` has anything but A, C, G and T
+pub fn kmer_hash(kmer: &[u8]) -> Option<u64> {
+    kmer.iter().try_fold(0u64, |hash, &base| {
+        Some(hash.rotate_left(1) ^ SEEDS[code(base)? as usize])
+    })
+}
+
+fn complement_seed(code: u8) -> u64 {
+    SEEDS[3 - code as usize]
+}
+
+// The hash of the reverse complement of `kmer`, without building it: base i of
+// `kmer` ends up i bases from the end of the reverse complement
+pub fn reverse_complement_hash(kmer: &[u8]) -> Option<u64> {
+    kmer.iter().enumerate().try_fold(0u64, |hash, (i, &base)| {
+        Some(hash ^ complement_seed(code(base)?).rotate_left(i as u32))
+    })
+}
+
+pub fn canonical_kmer_hash(kmer: &[u8]) -> Option<u64> {
+    Some(kmer_hash(kmer)?.min(reverse_complement_hash(kmer)?))
+}
+
+// A sequence of `len` bases packed four to a byte, first base in the two most
+// significant bits, with the codes A = 0, C = 1, G = 2, T = 3. It is half the
+// size of the same bases in ASCII, and cannot hold anything but A, C, G and T
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedSeq<'a> {
+    bytes: &'a [u8],
+    len: usize,
+}
+
+impl<'a> PackedSeq<'a> {
+    // The last byte may be partly used, so the length is not implied by `bytes`
+    pub fn new(bytes: &'a [u8], len: usize) -> Self {
+        assert!(
+            len.div_ceil(4) <= bytes.len(),
+            "{len} bases do not fit in {} bytes",
+            bytes.len()
+        );
+        Self { bytes, len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn code(&self, index: usize) -> u8 {
+        let shift = 6 - 2 * (index % 4);
+        (self.bytes[index / 4] >> shift) & 0b11
+    }
+}
+
+// Packs ASCII bases for PackedSeq::new(&packed, seq.len()). None if `seq` has
+// anything but A, C, G and T
+pub fn pack(seq: &[u8]) -> Option<Vec<u8>> {
+    seq.chunks(4)
+        .map(|bases| {
+            bases.iter().enumerate().try_fold(0, |byte, (i, &base)| {
+                Some(byte | code(base)? << (6 - 2 * i))
+            })
+        })
+        .collect()
+}
+
+// (position, hash) of every k-mer of `seq`, in order, skipping those that contain
+// anything but A, C, G and T
+pub fn kmer_hashes(seq: &[u8], k: usize) -> KmerHashes<'_> {
+    KmerHashes::new(Bases::Ascii(seq), k, false)
+}
+
+// Same hashes as kmer_hashes() on the unpacked bases. Bases are unpacked one at
+// a time as the window rolls over them
+pub fn kmer_hashes_packed(seq: PackedSeq<'_>, k: usize) -> KmerHashes<'_> {
+    KmerHashes::new(Bases::Packed(seq), k, false)
+}
+
+// Like kmer_hashes(), with canonical_kmer_hash() for every k-mer. The reverse
+// complement hash rolls along with the forward one
+pub fn canonical_kmer_hashes(seq: &[u8], k: usize) -> KmerHashes<'_> {
+    KmerHashes::new(Bases::Ascii(seq), k, true)
+}
+
+pub fn canonical_kmer_hashes_packed(seq: PackedSeq<'_>, k: usize) -> KmerHashes<'_> {
+    KmerHashes::new(Bases::Packed(seq), k, true)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Bases<'a> {
+    Ascii(&'a [u8]),
+    Packed(PackedSeq<'a>),
+}
+
+impl Bases<'_> {
+    fn len(&self) -> usize {
+        match self {
+            Bases::Ascii(seq) => seq.len(),
+            Bases::Packed(seq) => seq.len(),
+        }
+    }
+
+    fn code(&self, index: usize) -> Option<u8> {
+        match self {
+            Bases::Ascii(seq) => code(seq[index]),
+            Bases::Packed(seq) => Some(seq.code(index)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct KmerHashes<'a> {
+    seq: Bases<'a>,
+    k: usize,
+    // The next base to roll in
+    next: usize,
+    // How many valid bases end right before `next`, capped at k
+    run: usize,
+    forward: u64,
+    // Only kept up to date when `canonical` is set
+    reverse: u64,
+    canonical: bool,
+}
+
+impl<'a> KmerHashes<'a> {
+    fn new(seq: Bases<'a>, k: usize, canonical: bool) -> Self {
+        assert!(k > 0, "k must be positive");
+        Self {
+            seq,
+            k,
+            next: 0,
+            run: 0,
+            forward: 0,
+            reverse: 0,
+            canonical,
+        }
+    }
+}
+
+impl Iterator for KmerHashes<'_> {
+    type Item = (usize, u64);
+
+    fn next(&mut self) -> Option<(usize, u64)> {
+        while self.next < self.seq.len() {
+            let entering = self.seq.code(self.next);
+            self.next += 1;
+            let Some(entering) = entering else {
+                self.run = 0;
+                self.forward = 0;
+                self.reverse = 0;
+                continue;
+            };
+            self.forward = self.forward.rotate_left(1) ^ SEEDS[entering as usize];
+            if self.run == self.k {
+                // The leaving base has been rotated k times by now
+                let leaving = self.seq.code(self.next - 1 - self.k);
+                let leaving = leaving.expect("inside a valid run");
+                self.forward ^= SEEDS[leaving as usize].rotate_left(self.k as u32);
+                if self.canonical {
+                    // In the reverse complement, the leaving base is the last one
+                    // and the entering base becomes the first
+                    self.reverse = (self.reverse ^ complement_seed(leaving)).rotate_right(1);
+                    self.reverse ^= complement_seed(entering).rotate_left(self.k as u32 - 1);
+                }
+            } else {
+                if self.canonical {
+                    self.reverse ^= complement_seed(entering).rotate_left(self.run as u32);
+                }
+                self.run += 1;
+            }
+            if self.run == self.k {
+                let hash = if self.canonical {
+                    self.forward.min(self.reverse)
+                } else {
+                    self.forward
+                };
+                return Some((self.next - self.k, hash));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let ahead = self.seq.len() - self.next;
+        // Every base ahead can complete at most one k-mer
+        let upper = (ahead + self.run + 1).saturating_sub(self.k).min(ahead);
+        (0, Some(upper))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::nthash::{
+        canonical_kmer_hash, canonical_kmer_hashes, canonical_kmer_hashes_packed, kmer_hash,
+        kmer_hashes, kmer_hashes_packed, pack, reverse_complement_hash, PackedSeq,
+    };
+
+    #[test]
+    fn single_bases_are_their_seeds() {
+        assert_eq!(kmer_hash(b"A"), Some(0x3c8b_fbb3_95c6_0474));
+        assert_eq!(kmer_hash(b"t"), kmer_hash(b"T"));
+        assert_eq!(kmer_hash(b"ACGN"), None);
+    }
+
+    #[test]
+    fn k_mers_around_other_bases_are_skipped() {
+        let positions: Vec<usize> = kmer_hashes(b"ACGTNACGTA", 3)
+            .map(|(position, _)| position)
+            .collect();
+        assert_eq!(positions, vec![0, 1, 5, 6, 7]);
+        assert_eq!(kmer_hashes(b"ACGTNACGTA", 11).next(), None);
+    }
+
+    #[test]
+    fn both_strands_hash_the_same() {
+        assert_eq!(reverse_complement_hash(b"AACG"), kmer_hash(b"CGTT"));
+        assert_eq!(canonical_kmer_hash(b"AACG"), canonical_kmer_hash(b"cgtt"));
+        assert_ne!(kmer_hash(b"AACG"), kmer_hash(b"CGTT"));
+    }
+
+    #[test]
+    fn packing() {
+        assert_eq!(pack(b"ACGTtg"), Some(vec![0b00_01_10_11, 0b11_10_00_00]));
+        assert_eq!(pack(b"ACGN"), None);
+        assert_eq!(PackedSeq::new(&[0; 2], 6).len(), 6);
+    }
+
+    proptest! {
+        #[test]
+        fn rolling_matches_hashing_each_k_mer(
+            seq in prop::collection::vec(prop::sample::select(b"ACGTacgtN".to_vec()), 0..200),
+            k in 1..70usize,
+        ) {
+            let expected: Vec<(usize, u64)> = seq
+                .windows(k)
+                .enumerate()
+                .filter_map(|(position, kmer)| Some((position, kmer_hash(kmer)?)))
+                .collect();
+            prop_assert_eq!(kmer_hashes(&seq, k).collect::<Vec<_>>(), expected);
+        }
+
+        #[test]
+        fn rolling_matches_canonical_hashing_of_each_k_mer(
+            seq in prop::collection::vec(prop::sample::select(b"ACGTacgtN".to_vec()), 0..200),
+            k in 1..70usize,
+        ) {
+            let expected: Vec<(usize, u64)> = seq
+                .windows(k)
+                .enumerate()
+                .filter_map(|(position, kmer)| Some((position, canonical_kmer_hash(kmer)?)))
+                .collect();
+            prop_assert_eq!(canonical_kmer_hashes(&seq, k).collect::<Vec<_>>(), expected);
+        }
+
+        #[test]
+        fn packed_sequences_hash_like_ascii(
+            seq in prop::collection::vec(prop::sample::select(b"ACGT".to_vec()), 0..200),
+            k in 1..70usize,
+        ) {
+            let packed = pack(&seq).unwrap();
+            let packed = PackedSeq::new(&packed, seq.len());
+            prop_assert_eq!(
+                kmer_hashes_packed(packed, k).collect::<Vec<_>>(),
+                kmer_hashes(&seq, k).collect::<Vec<_>>()
+            );
+            prop_assert_eq!(
+                canonical_kmer_hashes_packed(packed, k).collect::<Vec<_>>(),
+                canonical_kmer_hashes(&seq, k).collect::<Vec<_>>()
+            );
+        }
+    }
+}
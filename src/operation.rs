@@ -0,0 +1,70 @@
+use std::collections::VecDeque;
+
+use crate::RollingHash;
+
+// One edit to a window, so that sequences of edits can be generated, stored and
+// replayed, e.g. by property tests checking a RollingHash against plain bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Operation {
+    PushBack(u8),
+    PushFront(u8),
+    PopBack,
+    PopFront,
+}
+
+impl Operation {
+    pub fn apply(self, rh: &mut RollingHash) {
+        match self {
+            Operation::PushBack(b) => rh.push_back(b),
+            Operation::PushFront(b) => rh.push_front(b),
+            Operation::PopBack => rh.pop_back(),
+            Operation::PopFront => rh.pop_front(),
+        }
+    }
+
+    // The same edit on plain bytes. There is no window bound here, so this only
+    // mirrors a RollingHash without max_window or a byte map
+    pub fn apply_to_bytes(self, bytes: &mut VecDeque<u8>) {
+        match self {
+            Operation::PushBack(b) => bytes.push_back(b),
+            Operation::PushFront(b) => bytes.push_front(b),
+            Operation::PopBack => {
+                bytes.pop_back();
+            }
+            Operation::PopFront => {
+                bytes.pop_front();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use crate::operation::Operation;
+    use crate::RollingHash;
+
+    #[test]
+    fn replay_matches_plain_bytes() {
+        let operations = [
+            Operation::PushBack(b'i'),
+            Operation::PushBack(b'g'),
+            Operation::PushFront(b'E'),
+            Operation::PopBack,
+            Operation::PopBack,
+            Operation::PopBack,
+            Operation::PopFront,
+            Operation::PushBack(b'e'),
+        ];
+        let mut rh = RollingHash::new();
+        let mut bytes = VecDeque::new();
+        for operation in operations {
+            operation.apply(&mut rh);
+            operation.apply_to_bytes(&mut bytes);
+        }
+        assert_eq!(bytes, [b'e']);
+        assert_eq!(rh, RollingHash::from_initial_bytes(b"e"));
+    }
+}
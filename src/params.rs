@@ -0,0 +1,122 @@
+// Named (base, modulus) pairs, so formats can record the profile they were
+// hashed with instead of repeating the numbers, and pick it again with by_name()
+// and RollingHash::set_params(). All of them use RollingHash's modulus, as that
+// one is fixed. DEFAULT is only what RollingHash starts with: after set_base()
+// or randomize_base(), params() reports the base in use
+
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashParams {
+    // "modulus/base", so that every base has a name of its own
+    pub name: Cow<'static, str>,
+    pub base: u64,
+    pub modulus: u64,
+}
+
+// What RollingHash uses unless its base is changed
+pub const DEFAULT: HashParams = HashParams {
+    name: Cow::Borrowed("1e9+7/257"),
+    base: 257,
+    modulus: 1_000_000_007,
+};
+
+// The other base common in competitive programming
+pub const BASE_131: HashParams = HashParams {
+    name: Cow::Borrowed("1e9+7/131"),
+    base: 131,
+    modulus: 1_000_000_007,
+};
+
+// A base close to the modulus, so that short inputs already wrap around it
+pub const LARGE_BASE: HashParams = HashParams {
+    name: Cow::Borrowed("1e9+7/911382323"),
+    base: 911_382_323,
+    modulus: 1_000_000_007,
+};
+
+pub const ALL: [HashParams; 3] = [DEFAULT, BASE_131, LARGE_BASE];
+
+pub fn by_name(name: &str) -> Option<HashParams> {
+    ALL.into_iter().find(|params| params.name == name)
+}
+
+impl HashParams {
+    // One-shot polynomial hash with these parameters, using the same scheme as
+    // RollingHash (the last byte has exponent 0). Products go through u128, so
+    // moduli up to 2^64 are fine
+    pub fn hash(&self, bytes: &[u8]) -> u64 {
+        let modulus = self.modulus as u128;
+        bytes.iter().fold(0, |hash, &byte| {
+            ((hash as u128 * self.base as u128 + byte as u128) % modulus) as u64
+        })
+    }
+
+    // For data that records the name of the profile it was hashed with
+    pub fn check_name(&self, name: &str) -> crate::Result<()> {
+        if name != self.name.as_ref() {
+            return Err(crate::Error::ParameterMismatch {
+                expected: self.name.to_string(),
+                found: name.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::params::{by_name, ALL, DEFAULT, LARGE_BASE};
+    use crate::RollingHash;
+
+    #[test]
+    fn default_matches_the_rolling_hash() {
+        let rh = RollingHash::from_initial_bytes(b"Eiger");
+        assert_eq!(rh.params(), DEFAULT);
+        assert_eq!(DEFAULT.base, rh.base());
+        assert_eq!(DEFAULT.modulus, rh.modulus());
+        assert_eq!(DEFAULT.hash(b"Eiger"), rh.get_current_hash());
+    }
+
+    #[test]
+    fn profiles_are_found_by_name() {
+        for params in ALL {
+            assert_eq!(by_name(&params.name), Some(params.clone()));
+            assert!(params.base > 1 && params.base < params.modulus);
+        }
+        assert_eq!(by_name("1e9+7/911382323"), Some(LARGE_BASE));
+        assert_eq!(by_name("Eiger"), None);
+    }
+
+    #[test]
+    fn every_profile_rolls_like_its_one_shot_hash() {
+        for params in ALL {
+            let mut rh = RollingHash::from_initial_bytes(b"Eiger");
+            rh.set_params(&params);
+            rh.pop_front();
+            rh.extend_from_slice(b", Moench");
+            assert_eq!(rh.params(), params.clone());
+            assert_eq!(rh.get_current_hash(), params.hash(b"iger, Moench"));
+        }
+    }
+
+    #[test]
+    fn check_name_reports_a_mismatch() {
+        assert!(DEFAULT.check_name("1e9+7/257").is_ok());
+        assert!(matches!(
+            DEFAULT.check_name("998244353/131"),
+            Err(crate::Error::ParameterMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn custom_bases_have_names_of_their_own() {
+        let mut rh1 = RollingHash::new();
+        let mut rh2 = RollingHash::new();
+        rh1.set_base(1234);
+        rh2.set_base(4321);
+        assert_eq!(rh1.params().name, "1e9+7/1234");
+        assert!(rh1.params().check_name(&rh1.params().name).is_ok());
+        assert!(rh1.params().check_name(&rh2.params().name).is_err());
+    }
+}
@@ -0,0 +1,237 @@
+use std::sync::Arc;
+
+use crate::powers::PowerTable;
+use crate::Numeric;
+
+// An immutable RollingHash: push_back and pop_front leave `self` untouched and
+// return a new handle. The window is a treap (see rope.rs) whose nodes are shared
+// through Arc, so a new handle only copies the O(log n) expected nodes on the path
+// it changed. Cloning a handle is O(1), which suits backtracking searches that
+// keep many states around
+#[derive(Clone)]
+pub struct PersistentRollingHash {
+    root: Link,
+    base_powers: PowerTable,
+}
+
+type Link = Option<Arc<Node>>;
+
+struct Node {
+    byte: u8,
+    priority: u32,
+    left: Link,
+    right: Link,
+    size: usize,
+    hash: Numeric,
+}
+
+impl Node {
+    fn new(byte: u8, priority: u32, left: Link, right: Link, powers: &PowerTable) -> Link {
+        let right_size = size(&right);
+        Some(Arc::new(Node {
+            byte,
+            priority,
+            size: size(&left) + 1 + right_size,
+            hash: hash(&left) * powers.get(right_size + 1)
+                + powers.get(right_size) * byte as u64
+                + hash(&right),
+            left,
+            right,
+        }))
+    }
+}
+
+fn size(link: &Link) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+fn hash(link: &Link) -> Numeric {
+    link.as_ref().map_or(Numeric::from_u64(0), |node| node.hash)
+}
+
+// Like rope.rs, but copies the nodes it changes instead of mutating them
+fn split(link: &Link, at: usize, powers: &PowerTable) -> (Link, Link) {
+    let Some(node) = link else {
+        return (None, None);
+    };
+    let left_size = size(&node.left);
+    if at <= left_size {
+        let (left, middle) = split(&node.left, at, powers);
+        let right = Node::new(node.byte, node.priority, middle, node.right.clone(), powers);
+        (left, right)
+    } else {
+        let (middle, right) = split(&node.right, at - left_size - 1, powers);
+        let left = Node::new(node.byte, node.priority, node.left.clone(), middle, powers);
+        (left, right)
+    }
+}
+
+fn merge(left: &Link, right: &Link, powers: &PowerTable) -> Link {
+    match (left, right) {
+        (None, link) | (link, None) => link.clone(),
+        (Some(l), Some(r)) => {
+            if l.priority > r.priority {
+                let merged = merge(&l.right, right, powers);
+                Node::new(l.byte, l.priority, l.left.clone(), merged, powers)
+            } else {
+                let merged = merge(left, &r.left, powers);
+                Node::new(r.byte, r.priority, merged, r.right.clone(), powers)
+            }
+        }
+    }
+}
+
+fn collect(link: &Link, bytes: &mut Vec<u8>) {
+    if let Some(node) = link {
+        collect(&node.left, bytes);
+        bytes.push(node.byte);
+        collect(&node.right, bytes);
+    }
+}
+
+impl PersistentRollingHash {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            base_powers: PowerTable::new(),
+        }
+    }
+
+    pub fn from_initial_bytes(bytes: &[u8]) -> Self {
+        bytes
+            .iter()
+            .fold(Self::new(), |state, &byte| state.push_back(byte))
+    }
+
+    pub fn get_current_hash(&self) -> u64 {
+        hash(&self.root).value()
+    }
+
+    pub fn get_current_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.len());
+        collect(&self.root, &mut bytes);
+        bytes
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    #[must_use]
+    pub fn push_back(&self, byte: u8) -> Self {
+        let mut base_powers = self.base_powers.clone();
+        base_powers.ensure(self.len() + 1);
+        let new_node = Node::new(byte, rand::random(), None, None, &base_powers);
+        Self {
+            root: merge(&self.root, &new_node, &base_powers),
+            base_powers,
+        }
+    }
+
+    #[must_use]
+    pub fn push_front(&self, byte: u8) -> Self {
+        let mut base_powers = self.base_powers.clone();
+        base_powers.ensure(self.len() + 1);
+        let new_node = Node::new(byte, rand::random(), None, None, &base_powers);
+        Self {
+            root: merge(&new_node, &self.root, &base_powers),
+            base_powers,
+        }
+    }
+
+    // Popping from an empty window gives back an empty window
+    #[must_use]
+    pub fn pop_front(&self) -> Self {
+        let (_, rest) = split(&self.root, 1, &self.base_powers);
+        Self {
+            root: rest,
+            base_powers: self.base_powers.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn pop_back(&self) -> Self {
+        let (rest, _) = split(&self.root, self.len().saturating_sub(1), &self.base_powers);
+        Self {
+            root: rest,
+            base_powers: self.base_powers.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn roll(&self, byte: u8) -> Self {
+        self.pop_front().push_back(byte)
+    }
+}
+
+impl Default for PersistentRollingHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::persistent::PersistentRollingHash;
+    use crate::RollingHash;
+
+    fn hash_from_bytes(bytes: &[u8]) -> u64 {
+        RollingHash::from_initial_bytes(bytes).get_current_hash()
+    }
+
+    #[test]
+    fn old_states_are_untouched() {
+        let eiger = PersistentRollingHash::from_initial_bytes(b"Eiger");
+        let tiger = eiger.pop_front().push_front(b'T');
+        let iger = eiger.pop_front();
+
+        assert_eq!(eiger.get_current_bytes(), b"Eiger");
+        assert_eq!(eiger.get_current_hash(), hash_from_bytes(b"Eiger"));
+        assert_eq!(tiger.get_current_hash(), hash_from_bytes(b"Tiger"));
+        assert_eq!(iger.get_current_hash(), hash_from_bytes(b"iger"));
+    }
+
+    #[test]
+    fn branching_from_a_common_state() {
+        let base = PersistentRollingHash::from_initial_bytes(b"Eige");
+        let branches: Vec<_> = (b'a'..=b'z').map(|b| base.roll(b)).collect();
+        for (b, branch) in (b'a'..=b'z').zip(&branches) {
+            assert_eq!(
+                branch.get_current_hash(),
+                hash_from_bytes(&[b'i', b'g', b'e', b])
+            );
+        }
+        assert_eq!(base.len(), 4);
+    }
+
+    #[test]
+    fn popping_an_empty_window() {
+        let empty = PersistentRollingHash::new().pop_front().pop_back();
+        assert!(empty.is_empty());
+        assert_eq!(empty.get_current_hash(), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_the_mutable_hash(ops in prop::collection::vec((0..4u8, any::<u8>()), 0..60)) {
+            let mut mutable = RollingHash::new();
+            let mut persistent = PersistentRollingHash::new();
+            for (op, byte) in ops {
+                match op {
+                    0 => { mutable.push_back(byte); persistent = persistent.push_back(byte); }
+                    1 => { mutable.push_front(byte); persistent = persistent.push_front(byte); }
+                    2 => { mutable.pop_front(); persistent = persistent.pop_front(); }
+                    _ => { mutable.pop_back(); persistent = persistent.pop_back(); }
+                }
+                prop_assert_eq!(persistent.get_current_hash(), mutable.get_current_hash());
+            }
+            prop_assert_eq!(persistent.get_current_bytes(), mutable.get_current_bytes());
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{Numeric, RollingHash};
+
+// Every hasher needs the same powers of BASE, so instead of each one growing
+// its own vector we keep a single process-wide table. Hashers hold a snapshot
+// of it and only touch the lock when they need a power their snapshot lacks.
+// The table lives as long as the process, so it stops growing at SHARED_LIMIT
+// powers (512 KiB). A hasher whose window needs more copies the shared powers
+// into a private table, which is freed along with the hasher and its clones
+static SHARED_POWERS: Mutex<Option<Arc<Vec<Numeric>>>> = Mutex::new(None);
+
+const SHARED_LIMIT: usize = 1 << 16;
+
+#[derive(Debug, Clone)]
+pub(crate) struct PowerTable {
+    powers: Arc<Vec<Numeric>>,
+    // None for the shared powers of BASE. Private tables of BASE itself have
+    // Some(BASE)
+    custom_base: Option<u64>,
+}
+
+impl PowerTable {
+    pub fn new() -> Self {
+        let mut shared = SHARED_POWERS.lock().unwrap();
+        let powers = shared.get_or_insert_with(|| Arc::new(vec![Numeric::from_u64(1)]));
+        Self {
+            powers: Arc::clone(powers),
+            custom_base: None,
+        }
+    }
+
+    // Powers of any other base are not shared: such tables are rare, and the
+    // point of a different base is usually that nobody else knows it
+    pub fn with_base(base: u64) -> Self {
+        if base == RollingHash::BASE {
+            return Self::new();
+        }
+        Self {
+            powers: Arc::new(vec![Numeric::from_u64(1)]),
+            custom_base: Some(base),
+        }
+    }
+
+    pub fn get(&self, exponent: usize) -> Numeric {
+        self.powers[exponent]
+    }
+
+    #[cfg(test)]
+    pub fn is_shared_with(&self, other: &PowerTable) -> bool {
+        Arc::ptr_eq(&self.powers, &other.powers)
+    }
+
+    // Makes sure BASE^exponent is available
+    pub fn ensure(&mut self, exponent: usize) {
+        if exponent < self.powers.len() {
+            return;
+        }
+        if exponent >= SHARED_LIMIT {
+            self.custom_base.get_or_insert(RollingHash::BASE);
+        }
+        if let Some(base) = self.custom_base {
+            // Clones of a hasher share the table, so they may still see it
+            let powers = Arc::make_mut(&mut self.powers);
+            let new_len = (exponent + 1).max(2 * powers.len());
+            while powers.len() < new_len {
+                let &last_power = powers.last().unwrap();
+                powers.push(last_power * base);
+            }
+            return;
+        }
+
+        let mut shared = SHARED_POWERS.lock().unwrap();
+        let current = shared.as_ref().unwrap();
+        if exponent >= current.len() {
+            // Grow geometrically so that we rarely need to come back here
+            let new_len = (exponent + 1).max(2 * current.len()).min(SHARED_LIMIT);
+            let mut extended = Vec::with_capacity(new_len);
+            extended.extend_from_slice(current);
+            while extended.len() < new_len {
+                // We have constructed it with one value, and we never remove values
+                let &last_power = extended.last().unwrap();
+                extended.push(last_power * RollingHash::BASE);
+            }
+            *shared = Some(Arc::new(extended));
+        }
+        self.powers = Arc::clone(shared.as_ref().unwrap());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modular::Modular;
+    use crate::powers::{PowerTable, SHARED_LIMIT, SHARED_POWERS};
+    use crate::{Numeric, RollingHash};
+
+    #[test]
+    fn powers_are_correct() {
+        let mut table = PowerTable::new();
+        table.ensure(100);
+        let mut expected: Numeric = Modular::from_u64(1);
+        for exponent in 0..=100 {
+            assert_eq!(table.get(exponent), expected);
+            expected = expected * RollingHash::BASE;
+        }
+    }
+
+    #[test]
+    fn growing_keeps_previous_powers() {
+        let mut table = PowerTable::new();
+        table.ensure(10);
+        let before: Vec<Numeric> = (0..=10).map(|e| table.get(e)).collect();
+        table.ensure(5000);
+        let after: Vec<Numeric> = (0..=10).map(|e| table.get(e)).collect();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn other_bases_get_their_own_table() {
+        let mut table = PowerTable::with_base(131);
+        table.ensure(3);
+        assert_eq!(table.get(3), Modular::from_u64(131 * 131 * 131));
+        assert!(!table.is_shared_with(&PowerTable::new()));
+        assert!(PowerTable::with_base(RollingHash::BASE).is_shared_with(&PowerTable::new()));
+    }
+
+    #[test]
+    fn huge_windows_do_not_grow_the_shared_table() {
+        let mut table = PowerTable::new();
+        table.ensure(SHARED_LIMIT + 10);
+        let mut expected = PowerTable::new();
+        expected.ensure(SHARED_LIMIT - 1);
+        assert_eq!(
+            table.get(SHARED_LIMIT + 10),
+            expected.get(SHARED_LIMIT - 1) * Modular::from_u64(RollingHash::BASE).pow(11)
+        );
+        assert!(!table.is_shared_with(&PowerTable::new()));
+        let shared = SHARED_POWERS.lock().unwrap();
+        assert!(shared.as_ref().unwrap().len() <= SHARED_LIMIT);
+    }
+
+    #[test]
+    fn new_tables_see_powers_computed_by_others() {
+        let mut table = PowerTable::new();
+        table.ensure(321);
+        let other = PowerTable::new();
+        assert_eq!(other.get(321), table.get(321));
+    }
+}
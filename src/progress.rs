@@ -0,0 +1,60 @@
+// Periodic reports from long-running operations, e.g. to draw a progress bar
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Progress {
+    pub bytes_read: u64,
+    // How many of the bytes read were found elsewhere (e.g. in the basis)
+    pub bytes_matched: u64,
+    // None when the input length is not known up front
+    pub total_bytes: Option<u64>,
+}
+
+impl Progress {
+    pub fn percent_complete(&self) -> Option<f64> {
+        match self.total_bytes? {
+            0 => Some(100.0),
+            total => Some(100.0 * self.bytes_read as f64 / total as f64),
+        }
+    }
+}
+
+pub trait ProgressSink {
+    fn report(&mut self, progress: Progress);
+}
+
+// Any closure taking a Progress works as a sink
+impl<F: FnMut(Progress)> ProgressSink for F {
+    fn report(&mut self, progress: Progress) {
+        self(progress)
+    }
+}
+
+// Ignores every report
+impl ProgressSink for () {
+    fn report(&mut self, _: Progress) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::progress::{Progress, ProgressSink};
+
+    #[test]
+    fn percent_complete() {
+        let progress = Progress {
+            bytes_read: 25,
+            bytes_matched: 0,
+            total_bytes: Some(100),
+        };
+        assert_eq!(progress.percent_complete(), Some(25.0));
+        assert_eq!(Progress::default().percent_complete(), None);
+    }
+
+    #[test]
+    fn closures_are_sinks() {
+        let mut reports = Vec::new();
+        let mut sink = |progress: Progress| reports.push(progress.bytes_read);
+        sink.report(Progress::default());
+        ().report(Progress::default());
+        assert_eq!(reports, vec![0]);
+    }
+}
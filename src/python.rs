@@ -0,0 +1,119 @@
+// Python bindings, built as the `rolling_hash_rust` extension module with maturin
+// (see pyproject.toml), which builds the crate as a cdylib itself
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::windows::window_hashes_with_step;
+use crate::RollingHash;
+
+#[pyclass(name = "RollingHash")]
+struct PyRollingHash {
+    inner: RollingHash,
+}
+
+#[pymethods]
+impl PyRollingHash {
+    #[new]
+    #[pyo3(signature = (initial = None, max_window = None))]
+    fn new(initial: Option<&[u8]>, max_window: Option<usize>) -> PyResult<Self> {
+        let mut inner = match max_window {
+            Some(0) => return Err(PyValueError::new_err("max_window must be positive")),
+            Some(max_window) => RollingHash::with_max_window(max_window),
+            None => RollingHash::new(),
+        };
+        inner.extend_from_slice(initial.unwrap_or_default());
+        Ok(Self { inner })
+    }
+
+    fn push_back(&mut self, b: u8) {
+        self.inner.push_back(b);
+    }
+
+    fn push_front(&mut self, b: u8) {
+        self.inner.push_front(b);
+    }
+
+    fn pop_front(&mut self) {
+        self.inner.pop_front();
+    }
+
+    fn pop_back(&mut self) {
+        self.inner.pop_back();
+    }
+
+    fn roll(&mut self, b: u8) {
+        self.inner.pop_front();
+        self.inner.push_back(b);
+    }
+
+    fn extend(&mut self, data: &[u8]) {
+        self.inner.extend_from_slice(data);
+    }
+
+    #[getter]
+    fn hash(&self) -> u64 {
+        self.inner.get_current_hash()
+    }
+
+    #[getter]
+    fn bytes(&self) -> Vec<u8> {
+        self.inner.get_current_bytes()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+#[pyfunction]
+#[pyo3(name = "window_hashes", signature = (data, window, step = 1))]
+fn py_window_hashes(data: &[u8], window: usize, step: usize) -> PyResult<Vec<u64>> {
+    if window == 0 || step == 0 {
+        return Err(PyValueError::new_err("window and step must be positive"));
+    }
+    Ok(window_hashes_with_step(data, window, step).collect())
+}
+
+#[pymodule]
+fn rolling_hash_rust(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyRollingHash>()?;
+    module.add_function(wrap_pyfunction!(py_window_hashes, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::python::{py_window_hashes, PyRollingHash};
+    use crate::RollingHash;
+
+    #[test]
+    fn methods_forward_to_the_rolling_hash() {
+        let mut rh = PyRollingHash::new(Some(b"Eige"), None).unwrap();
+        rh.push_back(b'r');
+        assert_eq!(
+            rh.hash(),
+            RollingHash::from_initial_bytes(b"Eiger").get_current_hash()
+        );
+        rh.roll(b'!');
+        assert_eq!(rh.bytes(), b"iger!");
+        assert_eq!(rh.__len__(), 5);
+    }
+
+    #[test]
+    fn bounded_window_from_python() {
+        assert!(PyRollingHash::new(None, Some(0)).is_err());
+        let rh = PyRollingHash::new(Some(b"Eiger"), Some(3)).unwrap();
+        assert_eq!(rh.bytes(), b"ger");
+    }
+
+    #[test]
+    fn window_hashes_from_python() {
+        assert!(py_window_hashes(b"Eiger", 0, 1).is_err());
+        let hashes = py_window_hashes(b"Eiger", 5, 1).unwrap();
+        assert_eq!(
+            hashes,
+            vec![RollingHash::from_initial_bytes(b"Eiger").get_current_hash()]
+        );
+    }
+}
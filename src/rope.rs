@@ -0,0 +1,297 @@
+use std::ops::Range;
+
+use crate::powers::PowerTable;
+use crate::Numeric;
+
+// A byte sequence kept as a treap (a binary search tree by position, heap-ordered
+// by random priorities, so it stays balanced in expectation). Every node knows the
+// hash of its subtree, which makes insert, erase, split, merge and range_hash all
+// O(log n) expected. Hashes agree with RollingHash on the same bytes
+pub struct HashedRope {
+    root: Link,
+    base_powers: PowerTable,
+}
+
+type Link = Option<Box<Node>>;
+
+struct Node {
+    byte: u8,
+    priority: u32,
+    left: Link,
+    right: Link,
+    // Of the whole subtree
+    size: usize,
+    hash: Numeric,
+}
+
+impl Node {
+    fn new(byte: u8) -> Box<Node> {
+        Box::new(Node {
+            byte,
+            priority: rand::random(),
+            left: None,
+            right: None,
+            size: 1,
+            hash: Numeric::from_u64(byte as u64),
+        })
+    }
+
+    // Recomputes size and hash from the children: left, then byte, then right
+    fn update(&mut self, powers: &PowerTable) {
+        let right_size = size(&self.right);
+        self.size = size(&self.left) + 1 + right_size;
+        self.hash = hash(&self.left) * powers.get(right_size + 1)
+            + powers.get(right_size) * self.byte as u64
+            + hash(&self.right);
+    }
+}
+
+fn size(link: &Link) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+fn hash(link: &Link) -> Numeric {
+    link.as_ref().map_or(Numeric::from_u64(0), |node| node.hash)
+}
+
+// The first `at` bytes go left, the rest go right
+fn split(link: Link, at: usize, powers: &PowerTable) -> (Link, Link) {
+    let Some(mut node) = link else {
+        return (None, None);
+    };
+    let left_size = size(&node.left);
+    if at <= left_size {
+        let (left, middle) = split(node.left.take(), at, powers);
+        node.left = middle;
+        node.update(powers);
+        (left, Some(node))
+    } else {
+        let (middle, right) = split(node.right.take(), at - left_size - 1, powers);
+        node.right = middle;
+        node.update(powers);
+        (Some(node), right)
+    }
+}
+
+fn merge(left: Link, right: Link, powers: &PowerTable) -> Link {
+    match (left, right) {
+        (None, link) | (link, None) => link,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.right = merge(left.right.take(), Some(right), powers);
+                left.update(powers);
+                Some(left)
+            } else {
+                right.left = merge(Some(left), right.left.take(), powers);
+                right.update(powers);
+                Some(right)
+            }
+        }
+    }
+}
+
+// (hash, length) of the subtree's bytes in `range`, which is relative to the subtree
+fn range_hash(link: &Link, range: Range<usize>, powers: &PowerTable) -> (Numeric, usize) {
+    let Some(node) = link else {
+        return (Numeric::from_u64(0), 0);
+    };
+    if range.start >= range.end {
+        return (Numeric::from_u64(0), 0);
+    }
+    if range.start == 0 && range.end == node.size {
+        return (node.hash, node.size);
+    }
+
+    let left_size = size(&node.left);
+    let (mut hash, mut len) = range_hash(
+        &node.left,
+        range.start.min(left_size)..range.end.min(left_size),
+        powers,
+    );
+    if range.start <= left_size && left_size < range.end {
+        hash = hash * powers.get(1) + node.byte as u64;
+        len += 1;
+    }
+    let right_start = range.start.max(left_size + 1) - (left_size + 1);
+    let right_end = range.end.max(left_size + 1) - (left_size + 1);
+    let (right_hash, right_len) = range_hash(&node.right, right_start..right_end, powers);
+    (hash * powers.get(right_len) + right_hash, len + right_len)
+}
+
+fn collect(link: &Link, bytes: &mut Vec<u8>) {
+    if let Some(node) = link {
+        collect(&node.left, bytes);
+        bytes.push(node.byte);
+        collect(&node.right, bytes);
+    }
+}
+
+impl HashedRope {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            base_powers: PowerTable::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn hash(&self) -> u64 {
+        hash(&self.root).value()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.len());
+        collect(&self.root, &mut bytes);
+        bytes
+    }
+
+    // Panics if `index` > len()
+    pub fn insert(&mut self, index: usize, byte: u8) {
+        assert!(index <= self.len(), "index out of bounds");
+        self.base_powers.ensure(self.len() + 1);
+        let (left, right) = split(self.root.take(), index, &self.base_powers);
+        let left = merge(left, Some(Node::new(byte)), &self.base_powers);
+        self.root = merge(left, right, &self.base_powers);
+    }
+
+    // Removes and returns the byte at `index`. Panics if `index` is out of bounds
+    pub fn erase(&mut self, index: usize) -> u8 {
+        assert!(index < self.len(), "index out of bounds");
+        let (left, rest) = split(self.root.take(), index, &self.base_powers);
+        let (erased, right) = split(rest, 1, &self.base_powers);
+        self.root = merge(left, right, &self.base_powers);
+        erased.unwrap().byte
+    }
+
+    // Splits into bytes[..at] and bytes[at..]. Panics if `at` > len()
+    pub fn split(mut self, at: usize) -> (HashedRope, HashedRope) {
+        assert!(at <= self.len(), "index out of bounds");
+        let (left, right) = split(self.root.take(), at, &self.base_powers);
+        (
+            Self {
+                root: left,
+                base_powers: self.base_powers.clone(),
+            },
+            Self {
+                root: right,
+                base_powers: self.base_powers,
+            },
+        )
+    }
+
+    // Concatenates `self` and `other`
+    pub fn merge(mut self, mut other: HashedRope) -> HashedRope {
+        self.base_powers.ensure(self.len() + other.len());
+        self.root = merge(self.root.take(), other.root.take(), &self.base_powers);
+        self
+    }
+
+    // Panics if the range is out of bounds
+    pub fn range_hash(&self, range: Range<usize>) -> u64 {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range out of bounds"
+        );
+        range_hash(&self.root, range, &self.base_powers).0.value()
+    }
+}
+
+impl Default for HashedRope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&[u8]> for HashedRope {
+    fn from(bytes: &[u8]) -> Self {
+        let mut rope = Self::new();
+        rope.base_powers.ensure(bytes.len());
+        for &byte in bytes {
+            rope.root = merge(rope.root.take(), Some(Node::new(byte)), &rope.base_powers);
+        }
+        rope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::rope::HashedRope;
+    use crate::RollingHash;
+
+    fn hash_from_bytes(bytes: &[u8]) -> u64 {
+        RollingHash::from_initial_bytes(bytes).get_current_hash()
+    }
+
+    #[test]
+    fn insert_and_erase() {
+        let mut rope = HashedRope::from(&b"Eger"[..]);
+        rope.insert(1, b'i');
+        assert_eq!(rope.to_bytes(), b"Eiger");
+        assert_eq!(rope.hash(), hash_from_bytes(b"Eiger"));
+
+        assert_eq!(rope.erase(0), b'E');
+        rope.insert(0, b'T');
+        assert_eq!(rope.hash(), hash_from_bytes(b"Tiger"));
+        assert_eq!(rope.range_hash(1..4), hash_from_bytes(b"ige"));
+    }
+
+    #[test]
+    fn split_and_merge() {
+        let rope = HashedRope::from(&b"Eiger"[..]);
+        let (left, right) = rope.split(2);
+        assert_eq!(left.hash(), hash_from_bytes(b"Ei"));
+        assert_eq!(right.hash(), hash_from_bytes(b"ger"));
+
+        let swapped = right.merge(left);
+        assert_eq!(swapped.to_bytes(), b"gerEi");
+        assert_eq!(swapped.hash(), hash_from_bytes(b"gerEi"));
+    }
+
+    #[test]
+    fn empty_rope() {
+        let rope = HashedRope::new();
+        assert!(rope.is_empty());
+        assert_eq!(rope.hash(), 0);
+        assert_eq!(rope.range_hash(0..0), 0);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_a_vector(
+            bytes in prop::collection::vec(any::<u8>(), 0..30),
+            inserts in prop::collection::vec((any::<usize>(), any::<u8>()), 0..10),
+            erases in prop::collection::vec(any::<usize>(), 0..10),
+        ) {
+            let mut expected = bytes.clone();
+            let mut rope = HashedRope::from(&bytes[..]);
+            for (index, byte) in inserts {
+                let index = index % (expected.len() + 1);
+                expected.insert(index, byte);
+                rope.insert(index, byte);
+            }
+            for index in erases {
+                if expected.is_empty() {
+                    break;
+                }
+                let index = index % expected.len();
+                prop_assert_eq!(rope.erase(index), expected.remove(index));
+            }
+
+            prop_assert_eq!(rope.to_bytes(), expected.clone());
+            for l in 0..=expected.len() {
+                for r in l..=expected.len() {
+                    prop_assert_eq!(rope.range_hash(l..r), hash_from_bytes(&expected[l..r]));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use std::ops::Range;
+
+use crate::mismatch;
+use crate::powers::PowerTable;
+use crate::Numeric;
+
+// A byte string that can be edited in place while still answering "what is the
+// hash of bytes[l..r]" in O(log n). Hashes agree with RollingHash on the same bytes
+pub struct DynamicHashedString {
+    bytes: Vec<u8>,
+    // Perfect binary tree stored as an array: node i has children 2i and 2i + 1,
+    // leaves start at `leaves`. Padding leaves are empty and hash to 0
+    nodes: Vec<Node>,
+    leaves: usize,
+    base_powers: PowerTable,
+}
+
+#[derive(Clone, Copy)]
+struct Node {
+    hash: Numeric,
+    len: usize,
+}
+
+impl Node {
+    const EMPTY: Node = Node {
+        hash: Numeric { value: 0 },
+        len: 0,
+    };
+}
+
+impl DynamicHashedString {
+    pub fn new(bytes: &[u8]) -> Self {
+        let leaves = bytes.len().next_power_of_two();
+        let mut base_powers = PowerTable::new();
+        base_powers.ensure(bytes.len());
+
+        let mut nodes = vec![Node::EMPTY; 2 * leaves];
+        for (i, &byte) in bytes.iter().enumerate() {
+            nodes[leaves + i] = Node {
+                hash: Numeric::from_u64(byte as u64),
+                len: 1,
+            };
+        }
+
+        let mut string = Self {
+            bytes: bytes.to_vec(),
+            nodes,
+            leaves,
+            base_powers,
+        };
+        for i in (1..leaves).rev() {
+            string.nodes[i] = string.combine(string.nodes[2 * i], string.nodes[2 * i + 1]);
+        }
+        string
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn get(&self, index: usize) -> Option<u8> {
+        self.bytes.get(index).copied()
+    }
+
+    // Panics if `index` is out of bounds
+    pub fn set(&mut self, index: usize, byte: u8) {
+        assert!(index < self.len(), "index out of bounds");
+        self.bytes[index] = byte;
+
+        let mut node = self.leaves + index;
+        self.nodes[node].hash = Numeric::from_u64(byte as u64);
+        while node > 1 {
+            node /= 2;
+            self.nodes[node] = self.combine(self.nodes[2 * node], self.nodes[2 * node + 1]);
+        }
+    }
+
+    pub fn hash(&self) -> u64 {
+        self.nodes[1].hash.value()
+    }
+
+    // Panics if the range is out of bounds
+    pub fn range_hash(&self, range: Range<usize>) -> u64 {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range out of bounds"
+        );
+
+        // Walk up from both ends, collecting the nodes fully inside the range.
+        // Order matters, so the left and right parts are accumulated separately
+        let mut left = Node::EMPTY;
+        let mut right = Node::EMPTY;
+        let mut lo = range.start + self.leaves;
+        let mut hi = range.end + self.leaves;
+        while lo < hi {
+            if lo % 2 == 1 {
+                left = self.combine(left, self.nodes[lo]);
+                lo += 1;
+            }
+            if hi % 2 == 1 {
+                hi -= 1;
+                right = self.combine(self.nodes[hi], right);
+            }
+            lo /= 2;
+            hi /= 2;
+        }
+        self.combine(left, right).hash.value()
+    }
+
+    // As in FenwickHashedString: the first differing index, the shorter length
+    // for a prefix, or None if equal. Probabilistic, see mismatch::first_mismatch()
+    pub fn first_mismatch(&self, other: &Self) -> Option<usize> {
+        mismatch::first_mismatch(
+            self.len(),
+            other.len(),
+            |range| self.range_hash(range),
+            |range| other.range_hash(range),
+        )
+    }
+
+    // The hash of `left` followed by `right`
+    fn combine(&self, left: Node, right: Node) -> Node {
+        Node {
+            hash: left.hash * self.base_powers.get(right.len) + right.hash,
+            len: left.len + right.len,
+        }
+    }
+}
+
+impl From<&[u8]> for DynamicHashedString {
+    fn from(bytes: &[u8]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::segment_tree::DynamicHashedString;
+    use crate::RollingHash;
+
+    fn hash_from_bytes(bytes: &[u8]) -> u64 {
+        RollingHash::from_initial_bytes(bytes).get_current_hash()
+    }
+
+    #[test]
+    fn range_hashes_match_rolling_hash() {
+        let string = DynamicHashedString::new(b"Eiger");
+        assert_eq!(string.hash(), hash_from_bytes(b"Eiger"));
+        assert_eq!(string.range_hash(1..4), hash_from_bytes(b"ige"));
+        assert_eq!(string.range_hash(2..2), 0);
+    }
+
+    #[test]
+    fn first_mismatch_after_an_update() {
+        let mut string = DynamicHashedString::new(b"Eiger, Moench");
+        let other = DynamicHashedString::new(b"Eiger, Moench");
+        assert_eq!(string.first_mismatch(&other), None);
+        string.set(9, b'o');
+        assert_eq!(string.first_mismatch(&other), Some(9));
+        assert_eq!(
+            other.first_mismatch(&DynamicHashedString::new(b"Eiger")),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn set_updates_the_hashes() {
+        let mut string = DynamicHashedString::new(b"Eiger");
+        string.set(0, b'T');
+        assert_eq!(string.bytes(), b"Tiger");
+        assert_eq!(string.hash(), hash_from_bytes(b"Tiger"));
+        assert_eq!(string.range_hash(0..2), hash_from_bytes(b"Ti"));
+    }
+
+    #[test]
+    fn empty_string() {
+        let string = DynamicHashedString::new(b"");
+        assert!(string.is_empty());
+        assert_eq!(string.hash(), 0);
+        assert_eq!(string.range_hash(0..0), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn range_past_the_end() {
+        DynamicHashedString::new(b"Eiger").range_hash(3..6);
+    }
+
+    proptest! {
+        #[test]
+        fn every_range_after_updates(
+            bytes in prop::collection::vec(any::<u8>(), 1..40),
+            updates in prop::collection::vec((any::<usize>(), any::<u8>()), 0..10),
+        ) {
+            let mut expected = bytes.clone();
+            let mut string = DynamicHashedString::new(&bytes);
+            for (index, byte) in updates {
+                let index = index % expected.len();
+                expected[index] = byte;
+                string.set(index, byte);
+            }
+            for l in 0..expected.len() {
+                for r in l + 1..=expected.len() {
+                    prop_assert_eq!(string.range_hash(l..r), hash_from_bytes(&expected[l..r]));
+                }
+            }
+        }
+    }
+}
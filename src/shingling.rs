@@ -0,0 +1,86 @@
+use std::collections::VecDeque;
+
+use crate::windows::window_hashes;
+use crate::RollingHash;
+
+// Hashes of every run of `n` consecutive bytes
+pub fn shingles(input: &[u8], n: usize) -> impl Iterator<Item = u64> + '_ {
+    assert!(n > 0, "shingle size must be positive");
+    window_hashes(input, n)
+}
+
+// Hashes of every run of `n` consecutive whitespace-delimited tokens.
+// Each shingle hashes to the same value as its tokens joined by a single space,
+// so differences in spacing or line breaks do not change the shingles
+pub fn token_shingles(input: &[u8], n: usize) -> impl Iterator<Item = u64> + '_ {
+    assert!(n > 0, "shingle size must be positive");
+
+    let mut rh = RollingHash::new();
+    let mut token_lengths: VecDeque<usize> = VecDeque::new();
+    input
+        .split(|b| b.is_ascii_whitespace())
+        .filter(|token| !token.is_empty())
+        .filter_map(move |token| {
+            if token_lengths.len() == n {
+                // Remove the oldest token together with the space after it
+                let oldest = token_lengths.pop_front().unwrap();
+                (0..=oldest).for_each(|_| rh.pop_front());
+            }
+            if !token_lengths.is_empty() {
+                rh.push_back(b' ');
+            }
+            token.iter().for_each(|&b| rh.push_back(b));
+            token_lengths.push_back(token.len());
+
+            (token_lengths.len() == n).then(|| rh.get_current_hash())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shingling::{shingles, token_shingles};
+    use crate::RollingHash;
+
+    fn hash_from_string(string: &str) -> u64 {
+        RollingHash::from_initial_bytes(string.as_bytes()).get_current_hash()
+    }
+
+    #[test]
+    fn byte_shingles_hash_each_window() {
+        let hashes: Vec<u64> = shingles(b"Eiger", 3).collect();
+        assert_eq!(
+            hashes,
+            vec![
+                hash_from_string("Eig"),
+                hash_from_string("ige"),
+                hash_from_string("ger")
+            ]
+        );
+    }
+
+    #[test]
+    fn token_shingles_hash_joined_tokens() {
+        let hashes: Vec<u64> = token_shingles(b"the quick brown fox", 2).collect();
+        assert_eq!(
+            hashes,
+            vec![
+                hash_from_string("the quick"),
+                hash_from_string("quick brown"),
+                hash_from_string("brown fox")
+            ]
+        );
+    }
+
+    #[test]
+    fn token_shingles_ignore_spacing() {
+        let tidy: Vec<u64> = token_shingles(b"the quick brown fox", 3).collect();
+        let messy: Vec<u64> = token_shingles(b"  the\tquick\n\nbrown   fox \n", 3).collect();
+        assert_eq!(tidy, messy);
+    }
+
+    #[test]
+    fn too_few_tokens_yield_no_shingles() {
+        assert_eq!(token_shingles(b"only two", 3).count(), 0);
+        assert_eq!(token_shingles(b"", 1).count(), 0);
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::BTreeSet;
+
+use crate::windows::window_hashes;
+
+#[derive(Debug, Clone, Copy)]
+pub struct MinHasher {
+    k: usize,
+    window: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinHashSignature {
+    // The (at most) k smallest distinct scrambled window hashes, ascending
+    hashes: Vec<u64>,
+    k: usize,
+}
+
+// Polynomial hashes of short windows are ordered like their contents, so we
+// scramble them before ranking, otherwise "smallest" would mean "lexicographically
+// first" and the sample would be biased. This is the splitmix64 finalizer
+fn scramble(mut x: u64) -> u64 {
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xbf58476d1ce4e5b9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94d049bb133111eb);
+    x ^= x >> 31;
+    x
+}
+
+impl MinHasher {
+    pub fn new(k: usize, window: usize) -> Self {
+        assert!(k > 0, "k must be positive");
+        assert!(window > 0, "window must be positive");
+        Self { k, window }
+    }
+
+    pub fn signature(&self, document: &[u8]) -> MinHashSignature {
+        let mut smallest = BTreeSet::new();
+        for hash in window_hashes(document, self.window) {
+            smallest.insert(scramble(hash));
+            if smallest.len() > self.k {
+                smallest.pop_last();
+            }
+        }
+
+        MinHashSignature {
+            hashes: smallest.into_iter().collect(),
+            k: self.k,
+        }
+    }
+
+    pub fn similarity(&self, a: &[u8], b: &[u8]) -> f64 {
+        self.signature(a).jaccard(&self.signature(b))
+    }
+}
+
+impl MinHashSignature {
+    pub fn hashes(&self) -> &[u64] {
+        &self.hashes
+    }
+
+    // Bottom-k estimator: among the k smallest hashes of the union, the fraction
+    // that is present in both documents estimates |A ∩ B| / |A ∪ B|
+    pub fn jaccard(&self, other: &MinHashSignature) -> f64 {
+        assert_eq!(self.k, other.k, "signatures must use the same k");
+        if self.hashes.is_empty() && other.hashes.is_empty() {
+            // Both documents are shorter than the window
+            return 1.0;
+        }
+
+        let mine: BTreeSet<u64> = self.hashes.iter().copied().collect();
+        let theirs: BTreeSet<u64> = other.hashes.iter().copied().collect();
+        let union_sample: Vec<u64> = mine.union(&theirs).copied().take(self.k).collect();
+        let shared = union_sample
+            .iter()
+            .filter(|hash| mine.contains(hash) && theirs.contains(hash))
+            .count();
+
+        shared as f64 / union_sample.len() as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::similarity::MinHasher;
+
+    #[test]
+    fn identical_documents_are_fully_similar() {
+        let minhasher = MinHasher::new(64, 4);
+        let document = b"the quick brown fox jumps over the lazy dog";
+        assert_eq!(minhasher.similarity(document, document), 1.0);
+    }
+
+    #[test]
+    fn disjoint_documents_are_not_similar() {
+        let minhasher = MinHasher::new(64, 4);
+        let a = b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+        let b = b"bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+        assert_eq!(minhasher.similarity(a, b), 0.0);
+    }
+
+    #[test]
+    fn signature_keeps_at_most_k_hashes() {
+        let minhasher = MinHasher::new(8, 3);
+        let signature = minhasher.signature(b"abcdefghijklmnopqrstuvwxyz");
+        assert_eq!(signature.hashes().len(), 8);
+        assert!(signature.hashes().windows(2).all(|w| w[0] < w[1]));
+
+        // Short documents have fewer windows than k
+        let signature = minhasher.signature(b"abcd");
+        assert_eq!(signature.hashes().len(), 2);
+    }
+
+    #[test]
+    fn estimate_is_close_to_exact_jaccard() {
+        use std::collections::HashSet;
+
+        // Reference for random string generation: https://stackoverflow.com/a/54277357
+        use rand::{distributions::Alphanumeric, Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let base: Vec<u8> = (&mut rng).sample_iter(&Alphanumeric).take(5000).collect();
+        let mut edited = base.clone();
+        edited[2500..3500].copy_from_slice(
+            &(&mut rng)
+                .sample_iter(&Alphanumeric)
+                .take(1000)
+                .collect::<Vec<u8>>(),
+        );
+
+        let window = 8;
+        let windows = |bytes: &[u8]| -> HashSet<Vec<u8>> {
+            bytes.windows(window).map(|w| w.to_vec()).collect()
+        };
+        let (a, b) = (windows(&base), windows(&edited));
+        let exact = a.intersection(&b).count() as f64 / a.union(&b).count() as f64;
+
+        let estimate = MinHasher::new(256, window).similarity(&base, &edited);
+        assert!((estimate - exact).abs() < 0.1, "{estimate} vs {exact}");
+    }
+}
@@ -0,0 +1,92 @@
+use crate::RollingHash;
+
+// A window of at most `window_len` bytes where pushing is the only way in: once
+// the window is full, every push evicts the oldest byte and hands it back. No
+// pop_front() to forget, and no way to push past the bound
+#[derive(Debug, Clone)]
+pub struct SlidingWindowHash {
+    inner: RollingHash,
+}
+
+impl SlidingWindowHash {
+    pub fn new(window_len: usize) -> Self {
+        Self {
+            inner: RollingHash::with_max_window(window_len),
+        }
+    }
+
+    // Returns the evicted byte, if the window was already full
+    pub fn push(&mut self, b: u8) -> Option<u8> {
+        let evicted = if self.is_full() {
+            self.inner.current_bytes.front().copied()
+        } else {
+            None
+        };
+        self.inner.push_back(b);
+        evicted
+    }
+
+    pub fn get_current_hash(&self) -> u64 {
+        self.inner.get_current_hash()
+    }
+
+    pub fn get_current_bytes(&self) -> Vec<u8> {
+        self.inner.get_current_bytes()
+    }
+
+    pub fn window_len(&self) -> usize {
+        self.inner.max_window().expect("always bounded")
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.inner.is_full()
+    }
+
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    // For everything else a RollingHash can tell about its window
+    pub fn as_rolling_hash(&self) -> &RollingHash {
+        &self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sliding_window::SlidingWindowHash;
+    use crate::RollingHash;
+
+    #[test]
+    fn pushes_evict_once_full() {
+        let mut window = SlidingWindowHash::new(4);
+        let evicted: Vec<Option<u8>> = b"Eiger".iter().map(|&b| window.push(b)).collect();
+        assert_eq!(evicted, [None, None, None, None, Some(b'E')]);
+        assert_eq!(window.get_current_bytes(), b"iger");
+        assert_eq!(
+            window.get_current_hash(),
+            RollingHash::from_initial_bytes(b"iger").get_current_hash()
+        );
+        assert!(window.is_full());
+        assert_eq!(window.window_len(), 4);
+    }
+
+    #[test]
+    fn reset_empties_the_window() {
+        let mut window = SlidingWindowHash::new(2);
+        window.push(b'E');
+        window.push(b'i');
+        window.reset();
+        assert!(window.is_empty());
+        assert_eq!(window.push(b'g'), None);
+        assert_eq!(window.as_rolling_hash().max_window(), Some(2));
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::io::{self, Read, Seek, SeekFrom};
+
+use crate::RollingHash;
+
+const BUFFER_SIZE: usize = 1 << 16;
+
+// Offsets of the first two identical `k`-byte windows in the stream, as
+// (earlier start, later start), where "first" means the later window ends as
+// early as possible. Only the hashes of past windows are kept in memory: a hash
+// match is confirmed by seeking back and re-reading the earlier window
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip(reader))
+)]
+pub fn first_duplicate_window<R: Read + Seek>(
+    mut reader: R,
+    k: usize,
+) -> crate::Result<Option<(u64, u64)>> {
+    assert!(k > 0, "k must be positive");
+    let mut window = RollingHash::with_max_window(k);
+    // Almost always a single offset, unless two different windows collide
+    let mut seen: HashMap<u64, Vec<u64>> = HashMap::new();
+    let mut buffer = vec![0; BUFFER_SIZE];
+    let mut offset = 0u64;
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(None),
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+
+        for &byte in &buffer[..read] {
+            window.push_back(byte);
+            offset += 1;
+            if window.len() < k {
+                continue;
+            }
+
+            let start = offset - k as u64;
+            let candidates = seen.entry(window.get_current_hash()).or_default();
+            if !candidates.is_empty() {
+                let current = window.get_current_bytes();
+                for &earlier in candidates.iter() {
+                    if read_window_at(&mut reader, earlier, k)? == current {
+                        #[cfg(feature = "tracing")]
+                        tracing::debug!(earlier, later = start, "found a repeated window");
+                        return Ok(Some((earlier, start)));
+                    }
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(earlier, later = start, "hash collision");
+                }
+            }
+            candidates.push(start);
+        }
+    }
+}
+
+// Reads `len` bytes at `offset` and then puts the reader back where it was
+fn read_window_at<R: Read + Seek>(reader: &mut R, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+    let position = reader.stream_position()?;
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes)?;
+    reader.seek(SeekFrom::Start(position))?;
+    Ok(bytes)
+}
+
+// Offset of the first byte where the two streams differ, or None if they are
+// identical. A stream that ends early differs from the other at its length.
+// Both blocks are in memory anyway, so they are compared byte by byte: a hash
+// would cost the same pass over them and could be fooled by a collision
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn first_divergence<A: Read, B: Read>(mut a: A, mut b: B) -> crate::Result<Option<u64>> {
+    let mut block_a = vec![0; BUFFER_SIZE];
+    let mut block_b = vec![0; BUFFER_SIZE];
+    let mut offset = 0u64;
+
+    loop {
+        let len_a = fill(&mut a, &mut block_a)?;
+        let len_b = fill(&mut b, &mut block_b)?;
+        let (block_a, block_b) = (&block_a[..len_a], &block_b[..len_b]);
+        if len_a == 0 && len_b == 0 {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(bytes = offset, "streams are identical");
+            return Ok(None);
+        }
+
+        if block_a != block_b {
+            let common = block_a
+                .iter()
+                .zip(block_b)
+                .position(|(x, y)| x != y)
+                .unwrap_or(len_a.min(len_b));
+            let divergence = offset + common as u64;
+            #[cfg(feature = "tracing")]
+            tracing::debug!(divergence, "streams diverge");
+            return Ok(Some(divergence));
+        }
+        offset += len_a as u64;
+    }
+}
+
+// Reads until `buffer` is full or the stream ends, unlike a single read()
+fn fill<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::stream::{first_divergence, first_duplicate_window};
+
+    #[test]
+    fn finds_the_first_repeat() {
+        let data = b"the Eiger, the Moench and the Jungfrau";
+        let found = first_duplicate_window(Cursor::new(data), 4).unwrap();
+        // "the " appears at 0 and again at 11
+        assert_eq!(found, Some((0, 11)));
+    }
+
+    #[test]
+    fn overlapping_windows_count() {
+        let found = first_duplicate_window(Cursor::new(b"xaaaa"), 3).unwrap();
+        assert_eq!(found, Some((1, 2)));
+    }
+
+    #[test]
+    fn no_repeat() {
+        assert_eq!(
+            first_duplicate_window(Cursor::new(b"Eiger"), 2).unwrap(),
+            None
+        );
+        assert_eq!(first_duplicate_window(Cursor::new(b"Ei"), 3).unwrap(), None);
+    }
+
+    #[test]
+    fn repeat_across_buffer_boundaries() {
+        let mut data: Vec<u8> = (0..100_000u32).flat_map(|i| i.to_le_bytes()).collect();
+        data.extend_from_within(70_000..70_016);
+        let found = first_duplicate_window(Cursor::new(&data), 16).unwrap();
+        // Both copies are preceded by the zero high byte of a counter,
+        // so the match starts one byte earlier
+        assert_eq!(found, Some((69_999, 399_999)));
+    }
+
+    #[test]
+    fn divergence_within_a_block() {
+        let found = first_divergence(Cursor::new(b"Eiger"), Cursor::new(b"Eigen")).unwrap();
+        assert_eq!(found, Some(4));
+        let found = first_divergence(Cursor::new(b"Eiger"), Cursor::new(b"Tiger")).unwrap();
+        assert_eq!(found, Some(0));
+    }
+
+    #[test]
+    fn colliding_streams_still_diverge() {
+        // Same hash (see hash_collision_example in lib.rs), different first byte
+        let s1 = b"ryIqVm6i3M25uvTttp2Qo8mlkWmKap5PkuWHtS3AZZkRBWCAE9jGCWpkgYHaQobJDJrhdwdoNRGjqQmaTAi5ZGo6hbslnzIL2HaP";
+        let s2 = b"eVCblKi7jexBFHudJsTfj8ibzxgXGlol8EthCd8OBniEXI6tVR9LFkNzPtNeqR3EIVERZwtG1uxFimT3cPQAHwTTiuRnj6gHh406";
+        let found = first_divergence(Cursor::new(s1), Cursor::new(s2)).unwrap();
+        assert_eq!(found, Some(0));
+    }
+
+    #[test]
+    fn one_stream_is_a_prefix_of_the_other() {
+        let found = first_divergence(Cursor::new(b"Eig"), Cursor::new(b"Eiger")).unwrap();
+        assert_eq!(found, Some(3));
+        let found = first_divergence(Cursor::new(b""), Cursor::new(b"Eiger")).unwrap();
+        assert_eq!(found, Some(0));
+    }
+
+    #[test]
+    fn identical_streams() {
+        let data = vec![7u8; 200_000];
+        let found = first_divergence(Cursor::new(&data), Cursor::new(&data)).unwrap();
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn divergence_after_many_blocks() {
+        let a = vec![7u8; 200_000];
+        let mut b = a.clone();
+        b[150_001] = 8;
+        let found = first_divergence(Cursor::new(&a), Cursor::new(&b)).unwrap();
+        assert_eq!(found, Some(150_001));
+    }
+}
@@ -0,0 +1,65 @@
+use crate::RollingHash;
+
+// Cryptographic hashes to confirm a weak (rolling) hash match.
+// Each variant is only available with the feature of the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StrongHashAlgorithm {
+    #[cfg(feature = "blake3")]
+    Blake3,
+    #[cfg(feature = "sha256")]
+    Sha256,
+}
+
+impl RollingHash {
+    // Hashes the bytes currently in the window, straight from the buffer
+    pub fn get_strong_hash(&self, algorithm: StrongHashAlgorithm) -> [u8; 32] {
+        let (front, back) = self.current_bytes.as_slices();
+        match algorithm {
+            #[cfg(feature = "blake3")]
+            StrongHashAlgorithm::Blake3 => {
+                let mut hasher = blake3::Hasher::new();
+                hasher.update(front);
+                hasher.update(back);
+                hasher.finalize().into()
+            }
+            #[cfg(feature = "sha256")]
+            StrongHashAlgorithm::Sha256 => {
+                use sha2::{Digest, Sha256};
+                let mut hasher = Sha256::new();
+                hasher.update(front);
+                hasher.update(back);
+                hasher.finalize().into()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::strong::StrongHashAlgorithm;
+    use crate::RollingHash;
+
+    // Pushing to the front makes the deque wrap around, so both slices are used
+    fn wrapped_eiger() -> RollingHash {
+        let mut rh = RollingHash::from_initial_bytes(b"ger");
+        rh.push_front(b'i');
+        rh.push_front(b'E');
+        rh
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn blake3_of_the_window() {
+        let strong = wrapped_eiger().get_strong_hash(StrongHashAlgorithm::Blake3);
+        assert_eq!(strong, *blake3::hash(b"Eiger").as_bytes());
+    }
+
+    #[cfg(feature = "sha256")]
+    #[test]
+    fn sha256_of_the_window() {
+        use sha2::{Digest, Sha256};
+        let strong = wrapped_eiger().get_strong_hash(StrongHashAlgorithm::Sha256);
+        assert_eq!(strong, <[u8; 32]>::from(Sha256::digest(b"Eiger")));
+    }
+}
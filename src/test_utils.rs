@@ -0,0 +1,98 @@
+// proptest strategies for crates that embed RollingHash, so their property tests
+// can drive it with the same inputs as ours and check it against reference_hash()
+
+use proptest::prelude::*;
+
+use crate::operation::Operation;
+use crate::params::{self, HashParams};
+use crate::RollingHash;
+
+pub fn bytes(max_len: usize) -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(any::<u8>(), 0..=max_len)
+}
+
+pub fn operation() -> impl Strategy<Value = Operation> {
+    prop_oneof![
+        any::<u8>().prop_map(Operation::PushBack),
+        any::<u8>().prop_map(Operation::PushFront),
+        Just(Operation::PopBack),
+        Just(Operation::PopFront),
+    ]
+}
+
+pub fn operations(max_len: usize) -> impl Strategy<Value = Vec<Operation>> {
+    prop::collection::vec(operation(), 0..=max_len)
+}
+
+pub fn profile() -> impl Strategy<Value = HashParams> {
+    prop::sample::select(params::ALL.to_vec())
+}
+
+// An empty hasher in any configuration the public API can produce: the base of
+// a named profile or any other one, bounded or not, with or without the
+// reversed hash. Compare it against its own params().hash(), not reference_hash()
+pub fn hasher() -> impl Strategy<Value = RollingHash> {
+    let base = prop_oneof![
+        profile().prop_map(|params| params.base),
+        2..params::DEFAULT.modulus,
+    ];
+    (base, prop::option::of(1..=64usize), any::<bool>()).prop_map(|(base, max_window, reversed)| {
+        let mut rh = match max_window {
+            Some(max_window) => RollingHash::with_max_window(max_window),
+            None => RollingHash::new(),
+        };
+        rh.set_base(base);
+        if reversed {
+            rh.track_reversed_hash();
+        }
+        rh
+    })
+}
+
+// What RollingHash should report for `bytes`, recomputed from scratch
+pub fn reference_hash(bytes: &[u8]) -> u64 {
+    params::DEFAULT.hash(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use proptest::prelude::*;
+
+    use crate::test_utils::{bytes, hasher, operations, reference_hash};
+    use crate::RollingHash;
+
+    proptest! {
+        #[test]
+        fn operations_keep_the_hash_in_sync(
+            initial in bytes(20),
+            operations in operations(100),
+        ) {
+            let mut rh = RollingHash::from_initial_bytes(&initial);
+            let mut model = VecDeque::from(initial);
+            for operation in operations {
+                operation.apply(&mut rh);
+                operation.apply_to_bytes(&mut model);
+                prop_assert_eq!(rh.get_current_hash(), reference_hash(model.make_contiguous()));
+            }
+        }
+
+        #[test]
+        fn every_configuration_hashes_with_its_params(
+            mut rh in hasher(),
+            operations in operations(100),
+        ) {
+            for operation in operations {
+                operation.apply(&mut rh);
+                let mut bytes = rh.get_current_bytes();
+                prop_assert!(rh.max_window().is_none_or(|max| bytes.len() <= max));
+                prop_assert_eq!(rh.get_current_hash(), rh.params().hash(&bytes));
+                if let Some(reversed) = rh.get_reversed_hash() {
+                    bytes.reverse();
+                    prop_assert_eq!(reversed, rh.params().hash(&bytes));
+                }
+            }
+        }
+    }
+}
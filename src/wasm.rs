@@ -0,0 +1,119 @@
+// WebAssembly bindings. The library is only an rlib by default, so build the
+// module as a cdylib and then generate the JavaScript glue:
+//   cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib
+//   wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/rolling_hash_rust.wasm
+// u64 hashes cross into JavaScript as BigInt
+
+use wasm_bindgen::prelude::*;
+
+use crate::windows::window_hashes_with_step;
+use crate::RollingHash;
+
+#[wasm_bindgen(js_name = RollingHash)]
+pub struct WasmRollingHash {
+    inner: RollingHash,
+}
+
+#[wasm_bindgen(js_class = RollingHash)]
+impl WasmRollingHash {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            inner: RollingHash::new(),
+        }
+    }
+
+    #[wasm_bindgen(js_name = withMaxWindow)]
+    pub fn with_max_window(max_window: usize) -> Result<WasmRollingHash, JsError> {
+        if max_window == 0 {
+            return Err(JsError::new("maxWindow must be positive"));
+        }
+        Ok(Self {
+            inner: RollingHash::with_max_window(max_window),
+        })
+    }
+
+    #[wasm_bindgen(js_name = pushBack)]
+    pub fn push_back(&mut self, b: u8) {
+        self.inner.push_back(b);
+    }
+
+    #[wasm_bindgen(js_name = pushFront)]
+    pub fn push_front(&mut self, b: u8) {
+        self.inner.push_front(b);
+    }
+
+    #[wasm_bindgen(js_name = popFront)]
+    pub fn pop_front(&mut self) {
+        self.inner.pop_front();
+    }
+
+    #[wasm_bindgen(js_name = popBack)]
+    pub fn pop_back(&mut self) {
+        self.inner.pop_back();
+    }
+
+    pub fn roll(&mut self, b: u8) {
+        self.inner.pop_front();
+        self.inner.push_back(b);
+    }
+
+    // Takes a Uint8Array, e.g. the contents of a File read as an ArrayBuffer
+    pub fn extend(&mut self, data: &[u8]) {
+        self.inner.extend_from_slice(data);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn hash(&self) -> u64 {
+        self.inner.get_current_hash()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl Default for WasmRollingHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Returns a BigUint64Array
+#[wasm_bindgen(js_name = windowHashes)]
+pub fn window_hashes(data: &[u8], window: usize, step: usize) -> Result<Vec<u64>, JsError> {
+    if window == 0 || step == 0 {
+        return Err(JsError::new("window and step must be positive"));
+    }
+    Ok(window_hashes_with_step(data, window, step).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    // Error paths build JavaScript objects, so they can only be exercised on wasm32
+    use crate::wasm::{window_hashes, WasmRollingHash};
+    use crate::RollingHash;
+
+    #[test]
+    fn methods_forward_to_the_rolling_hash() {
+        let mut rh = WasmRollingHash::new();
+        rh.extend(b"Eige");
+        rh.push_back(b'r');
+        assert_eq!(
+            rh.hash(),
+            RollingHash::from_initial_bytes(b"Eiger").get_current_hash()
+        );
+        rh.roll(b'!');
+        assert_eq!(rh.length(), 5);
+    }
+
+    #[test]
+    fn window_hashes_from_javascript() {
+        let hashes = window_hashes(b"Eiger", 5, 1).ok().unwrap();
+        assert_eq!(
+            hashes,
+            vec![RollingHash::from_initial_bytes(b"Eiger").get_current_hash()]
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use crate::modular::Modular;
+use crate::{bulk, Numeric, RollingHash};
+
+// Hashes of every `window`-sized substring of `data`, in order. Unlike feeding a
+// RollingHash, this rolls directly over the borrowed bytes and never copies them,
+// so it works just as well over a memory-mapped file (which derefs to &[u8])
+pub fn window_hashes(data: &[u8], window: usize) -> WindowHashes<'_> {
+    WindowHashes::new(data, window)
+}
+
+// Hashes of every `step`-th window, e.g. step == window hashes non-overlapping blocks.
+// Skipped windows are never hashed: we roll over at most min(step, window) bytes
+// to get from one yielded window to the next
+pub fn window_hashes_with_step(data: &[u8], window: usize, step: usize) -> WindowHashes<'_> {
+    WindowHashes::with_step(data, window, step)
+}
+
+#[derive(Debug, Clone)]
+pub struct WindowHashes<'a> {
+    data: &'a [u8],
+    window: usize,
+    step: usize,
+    // Where the window whose hash is `hash` starts
+    start: usize,
+    hash: Numeric,
+    // BASE^(window-1), the weight of the byte leaving the window
+    leaving_power: Numeric,
+}
+
+impl<'a> WindowHashes<'a> {
+    pub fn new(data: &'a [u8], window: usize) -> Self {
+        Self::with_step(data, window, 1)
+    }
+
+    pub fn with_step(data: &'a [u8], window: usize, step: usize) -> Self {
+        assert!(window > 0, "window must be positive");
+        assert!(step > 0, "step must be positive");
+        let first_window = &data[..window.min(data.len())];
+        Self {
+            data,
+            window,
+            step,
+            start: 0,
+            hash: bulk::extend(Modular::from_u64(0), first_window),
+            leaving_power: Modular::from_u64(RollingHash::BASE).pow(window as u64 - 1),
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        if self.start + self.window > self.data.len() {
+            return 0;
+        }
+        (self.data.len() - self.window - self.start) / self.step + 1
+    }
+
+    fn advance(&mut self) {
+        let next_start = self.start + self.step;
+        let has_next_window = next_start + self.window <= self.data.len();
+        if !has_next_window {
+            self.start = next_start;
+            return;
+        }
+
+        if self.step >= self.window {
+            // Nothing to reuse, the next window does not overlap this one
+            let next_window = &self.data[next_start..next_start + self.window];
+            self.hash = bulk::extend(Modular::from_u64(0), next_window);
+        } else {
+            for leaving_index in self.start..next_start {
+                let leaving = self.data[leaving_index];
+                let entering = self.data[leaving_index + self.window];
+                self.hash = self.hash - self.leaving_power * leaving as u64;
+                self.hash = self.hash * RollingHash::BASE + entering as u64;
+            }
+        }
+        self.start = next_start;
+    }
+}
+
+impl Iterator for WindowHashes<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.remaining() == 0 {
+            return None;
+        }
+
+        let current = self.hash.value;
+        self.advance();
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining(), Some(self.remaining()))
+    }
+}
+
+impl ExactSizeIterator for WindowHashes<'_> {}
+
+// Same output as window_hashes(data, window).collect(), computed on all cores.
+// The windows are split into contiguous shards, each shard reading `window - 1`
+// bytes past its end so that no window is lost at the seams
+#[cfg(feature = "rayon")]
+pub fn hash_windows_parallel(data: &[u8], window: usize) -> Vec<u64> {
+    use rayon::prelude::*;
+
+    // Below this many windows per shard, the overhead outweighs the parallelism
+    const MIN_SHARD: usize = 1 << 14;
+
+    assert!(window > 0, "window must be positive");
+    if data.len() < window {
+        return Vec::new();
+    }
+
+    let total = data.len() - window + 1;
+    let shard = total
+        .div_ceil(4 * rayon::current_num_threads())
+        .max(MIN_SHARD);
+    let mut hashes = vec![0; total];
+    hashes
+        .par_chunks_mut(shard)
+        .enumerate()
+        .for_each(|(i, shard_hashes)| {
+            let start = i * shard;
+            let shard_data = &data[start..start + shard_hashes.len() + window - 1];
+            for (slot, hash) in shard_hashes
+                .iter_mut()
+                .zip(window_hashes(shard_data, window))
+            {
+                *slot = hash;
+            }
+        });
+    hashes
+}
+
+// Lets any byte iterator produce window hashes: `bytes.rolling_hashes(window)`.
+// For data that is already in memory, window_hashes() avoids buffering the window
+pub trait RollingHashIteratorExt: Iterator<Item = u8> + Sized {
+    fn rolling_hashes(self, window: usize) -> RollingHashes<Self> {
+        self.rolling_hashes_with_step(window, 1)
+    }
+
+    // Only every `step`-th window hash is yielded
+    fn rolling_hashes_with_step(self, window: usize, step: usize) -> RollingHashes<Self> {
+        assert!(step > 0, "step must be positive");
+        RollingHashes {
+            bytes: self,
+            window,
+            step,
+            to_skip: 0,
+            hasher: RollingHash::with_max_window(window),
+        }
+    }
+}
+
+impl<I: Iterator<Item = u8>> RollingHashIteratorExt for I {}
+
+pub struct RollingHashes<I> {
+    bytes: I,
+    window: usize,
+    step: usize,
+    // Full windows to pass over before yielding again
+    to_skip: usize,
+    hasher: RollingHash,
+}
+
+impl<I: Iterator<Item = u8>> Iterator for RollingHashes<I> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            self.hasher.push_back(self.bytes.next()?);
+            if self.hasher.len() < self.window {
+                continue;
+            }
+            if self.to_skip == 0 {
+                self.to_skip = self.step - 1;
+                return Some(self.hasher.get_current_hash());
+            }
+            self.to_skip -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::proptest;
+
+    use crate::windows::{window_hashes, window_hashes_with_step, RollingHashIteratorExt};
+    use crate::RollingHash;
+
+    fn naive_window_hashes(data: &[u8], window: usize) -> Vec<u64> {
+        data.windows(window)
+            .map(|w| RollingHash::from_initial_bytes(w).get_current_hash())
+            .collect()
+    }
+
+    #[test]
+    fn hashes_every_window() {
+        let hashes: Vec<u64> = window_hashes(b"Eiger", 2).collect();
+        assert_eq!(hashes, naive_window_hashes(b"Eiger", 2));
+        assert_eq!(hashes.len(), 4);
+    }
+
+    #[test]
+    fn window_longer_than_data_yields_nothing() {
+        assert_eq!(window_hashes(b"Eiger", 6).count(), 0);
+        assert_eq!(window_hashes(b"", 1).count(), 0);
+    }
+
+    #[test]
+    fn whole_data_is_a_single_window() {
+        let hashes: Vec<u64> = window_hashes(b"Eiger", 5).collect();
+        assert_eq!(
+            hashes,
+            vec![RollingHash::from_initial_bytes(b"Eiger").get_current_hash()]
+        );
+    }
+
+    #[test]
+    fn reports_exact_length() {
+        let mut hashes = window_hashes(b"a b c d e f g", 4);
+        assert_eq!(hashes.len(), 10);
+        hashes.next();
+        assert_eq!(hashes.len(), 9);
+    }
+
+    #[test]
+    fn iterator_adapter_hashes_every_window() {
+        let data = b"the quick brown fox";
+        let hashes: Vec<u64> = data.iter().copied().rolling_hashes(4).collect();
+        assert_eq!(hashes, naive_window_hashes(data, 4));
+    }
+
+    #[test]
+    fn iterator_adapter_composes_with_streams() {
+        let stream = std::iter::repeat(b"Eiger".iter().copied()).flatten();
+        let hashes: Vec<u64> = stream.rolling_hashes(5).take(6).collect();
+        assert_eq!(hashes[0], hashes[5]);
+        assert_eq!(
+            hashes[1],
+            RollingHash::from_initial_bytes(b"igerE").get_current_hash()
+        );
+    }
+
+    #[test]
+    fn step_hashes_every_kth_window() {
+        let data = b"the quick brown fox jumps";
+        let every_third: Vec<u64> = naive_window_hashes(data, 4)
+            .into_iter()
+            .step_by(3)
+            .collect();
+        let hashes: Vec<u64> = window_hashes_with_step(data, 4, 3).collect();
+        assert_eq!(hashes, every_third);
+        let hashes: Vec<u64> = data
+            .iter()
+            .copied()
+            .rolling_hashes_with_step(4, 3)
+            .collect();
+        assert_eq!(hashes, every_third);
+    }
+
+    #[test]
+    fn step_equal_to_window_hashes_blocks() {
+        let data = b"aaaabbbbccccdd";
+        let hashes: Vec<u64> = window_hashes_with_step(data, 4, 4).collect();
+        let blocks: Vec<u64> = data
+            .chunks_exact(4)
+            .map(|block| RollingHash::from_initial_bytes(block).get_current_hash())
+            .collect();
+        assert_eq!(hashes, blocks);
+        assert_eq!(window_hashes_with_step(data, 4, 4).len(), 3);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_matches_sequential() {
+        use crate::windows::hash_windows_parallel;
+
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i * 7 % 253) as u8).collect();
+        for window in [1, 16, 1000] {
+            let sequential: Vec<u64> = window_hashes(&data, window).collect();
+            assert_eq!(hash_windows_parallel(&data, window), sequential);
+        }
+        assert!(hash_windows_parallel(b"Eiger", 6).is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn matches_naive_hashing(
+            data in proptest::collection::vec(0..=255u8, 0..100),
+            window in 1..40usize,
+        ) {
+            let hashes: Vec<u64> = window_hashes(&data, window).collect();
+            assert_eq!(hashes, naive_window_hashes(&data, window));
+        }
+
+        #[test]
+        fn step_matches_naive_hashing(
+            data in proptest::collection::vec(0..=255u8, 0..100),
+            window in 1..20usize,
+            step in 1..30usize,
+        ) {
+            let expected: Vec<u64> = naive_window_hashes(&data, window).into_iter().step_by(step).collect();
+            let hashes = window_hashes_with_step(&data, window, step);
+            assert_eq!(hashes.len(), expected.len());
+            assert_eq!(hashes.collect::<Vec<u64>>(), expected);
+        }
+    }
+}
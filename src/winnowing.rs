@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+
+use crate::windows::window_hashes;
+
+// Winnowing, from "Winnowing: Local Algorithms for Document Fingerprinting"
+// (Schleimer, Wilkerson, Aiken), the algorithm behind MOSS.
+// In every window of `w` consecutive hashes we select the minimum (the rightmost
+// one on ties), and record it only once even if it is selected by many windows.
+// Returns (position, hash) pairs, where position indexes into `hashes`
+pub fn winnow(hashes: &[u64], w: usize) -> Vec<(usize, u64)> {
+    assert!(w > 0, "window must be positive");
+
+    let mut fingerprints: Vec<(usize, u64)> = Vec::new();
+    // Positions whose hashes are strictly increasing, so the front is the minimum
+    let mut candidates: VecDeque<usize> = VecDeque::new();
+    for (i, &hash) in hashes.iter().enumerate() {
+        // Using >= keeps the rightmost of equal hashes
+        while matches!(candidates.back(), Some(&back) if hashes[back] >= hash) {
+            candidates.pop_back();
+        }
+        candidates.push_back(i);
+
+        if candidates[0] + w <= i {
+            candidates.pop_front();
+        }
+
+        if i + 1 >= w {
+            let selected = candidates[0];
+            if fingerprints.last().map(|&(position, _)| position) != Some(selected) {
+                fingerprints.push((selected, hashes[selected]));
+            }
+        }
+    }
+
+    fingerprints
+}
+
+// Fingerprints a document by winnowing the hashes of its k-grams.
+// Positions are byte offsets of the selected k-grams
+pub fn fingerprint(document: &[u8], k: usize, w: usize) -> Vec<(usize, u64)> {
+    let hashes: Vec<u64> = window_hashes(document, k).collect();
+    winnow(&hashes, w)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::winnowing::{fingerprint, winnow};
+
+    #[test]
+    fn example_from_the_paper() {
+        // Section 4 of the winnowing paper, with w = 4
+        let hashes = [
+            77, 74, 42, 17, 98, 50, 17, 98, 8, 88, 67, 39, 77, 74, 42, 17, 98,
+        ];
+        let selected: Vec<u64> = winnow(&hashes, 4).into_iter().map(|(_, h)| h).collect();
+        assert_eq!(selected, vec![17, 17, 8, 39, 17]);
+    }
+
+    #[test]
+    fn ties_select_the_rightmost_minimum() {
+        let hashes = [5, 5, 5, 5];
+        assert_eq!(winnow(&hashes, 2), vec![(1, 5), (2, 5), (3, 5)]);
+    }
+
+    #[test]
+    fn fewer_hashes_than_window_selects_nothing() {
+        assert!(winnow(&[1, 2], 3).is_empty());
+    }
+
+    #[test]
+    fn every_window_contains_a_fingerprint() {
+        let hashes: Vec<u64> = (0..200u64).map(|i| (i * 7919) % 101).collect();
+        let w = 5;
+        let positions: Vec<usize> = winnow(&hashes, w).into_iter().map(|(p, _)| p).collect();
+        for start in 0..=hashes.len() - w {
+            assert!(positions.iter().any(|&p| p >= start && p < start + w));
+        }
+    }
+
+    #[test]
+    fn shared_substrings_share_fingerprints() {
+        let k = 5;
+        let w = 4;
+        let shared = b"a long enough passage copied verbatim between both documents";
+        let doc1 = [b"first document: ".as_slice(), shared].concat();
+        let doc2 = [b"something else entirely, then ".as_slice(), shared].concat();
+
+        let hashes1: Vec<u64> = fingerprint(&doc1, k, w)
+            .into_iter()
+            .map(|(_, h)| h)
+            .collect();
+        let hashes2: Vec<u64> = fingerprint(&doc2, k, w)
+            .into_iter()
+            .map(|(_, h)| h)
+            .collect();
+        assert!(hashes1.iter().any(|h| hashes2.contains(h)));
+    }
+}